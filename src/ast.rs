@@ -1,6 +1,9 @@
+use num::bigint::BigInt;
+
 #[derive(Debug)]
 pub struct AstData {
     pub line: usize,
+    pub col: usize,
     //pub start: usize,
     //pub end: usize
 }
@@ -10,6 +13,7 @@ pub struct AstData {
 pub enum Literal<'a> {
     Bool(bool),
     Int(i64),
+    BigInt(&'a BigInt),
     Float(f64),
     String(&'a str),
     Clip {
@@ -50,6 +54,12 @@ pub enum ExprType<'a> {
     },
     Tuple {
         values: Vec<Expr<'a>>,
+    },
+    List {
+        values: Vec<Expr<'a>>,
+    },
+    Interpolate {
+        parts: Vec<Expr<'a>>,
     }
 }
 
@@ -122,7 +132,14 @@ pub enum StmtType<'a> {
         iterator: Box<Expr<'a>>,
         statements: Vec<Stmt<'a>>,
     },
-    Return
+    Try {
+        statements: Vec<Stmt<'a>>,
+        ident: &'a str,
+        catch_statements: Vec<Stmt<'a>>,
+    },
+    Return,
+    Break,
+    Continue
 }
 
 //If statement clauses
@@ -0,0 +1,230 @@
+use std::error;
+use std::fmt::{Display, Formatter};
+use std::fmt::Result as FmtResult;
+
+use utils::get_caret_string;
+use evaluator::value::Value;
+
+//Position in a source file. Lines and columns start at 0, but are displayed starting at 1.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Span {
+    pub file: String,
+    pub line: usize,
+    pub col: usize,
+    pub line_string: String
+}
+
+impl Span {
+    pub fn new(file: &str, line: usize, col: usize, line_string: &str) -> Span {
+        Span {
+            file: file.to_string(),
+            line: line,
+            col: col,
+            line_string: line_string.to_string()
+        }
+    }
+
+    fn write_source_line(&self, f: &mut Formatter) -> FmtResult {
+        let line_as_string = (self.line + 1).to_string();
+        try!(write!(f, "\n{}: {}", line_as_string, self.line_string));
+        write!(f, "\n{}", get_caret_string(self.col + line_as_string.len() + 2))
+    }
+}
+
+impl Display for Span {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        if self.file.is_empty() {
+            write!(f, "{}:{}", self.line + 1, self.col + 1)
+        } else {
+            write!(f, "{}:{}:{}", self.file, self.line + 1, self.col + 1)
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    Lex {
+        span: Span,
+        message: String
+    },
+    Parse {
+        span: Span,
+        message: String,
+        hint: Option<String>
+    },
+    Codegen {
+        span: Span,
+        message: String
+    },
+    //Errors raised by native clips don't have a span until the VM fills in where they were played
+    Runtime {
+        span: Option<Span>,
+        message: String,
+        //Value that a script raised the error with, if it came from `error`
+        value: Option<Value>,
+        trace: Vec<Span>
+    },
+    Io {
+        span: Option<Span>,
+        message: String
+    }
+}
+
+impl Error {
+    pub fn runtime<D: Display>(message: D) -> Error {
+        Error::Runtime {
+            span: None,
+            message: message.to_string(),
+            value: None,
+            trace: Vec::new()
+        }
+    }
+
+    pub fn raised(value: Value) -> Error {
+        Error::Runtime {
+            span: None,
+            message: value.to_string(),
+            value: Some(value),
+            trace: Vec::new()
+        }
+    }
+
+    pub fn io<D: Display>(message: D) -> Error {
+        Error::Io {
+            span: None,
+            message: message.to_string()
+        }
+    }
+
+    pub fn span(&self) -> Option<&Span> {
+        match *self {
+            Error::Lex{ref span, ..} => Some(span),
+            Error::Parse{ref span, ..} => Some(span),
+            Error::Codegen{ref span, ..} => Some(span),
+            Error::Runtime{ref span, ..} => span.as_ref(),
+            Error::Io{ref span, ..} => span.as_ref()
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match *self {
+            Error::Lex{ref message, ..} => &message[..],
+            Error::Parse{ref message, ..} => &message[..],
+            Error::Codegen{ref message, ..} => &message[..],
+            Error::Runtime{ref message, ..} => &message[..],
+            Error::Io{ref message, ..} => &message[..]
+        }
+    }
+
+    //Sets the file name for errors from stages that only see the source text
+    pub fn in_file(mut self, name: &str) -> Error {
+        match self {
+            Error::Lex{ref mut span, ..} |
+            Error::Parse{ref mut span, ..} |
+            Error::Codegen{ref mut span, ..} => {
+                if span.file.is_empty() {
+                    span.file = name.to_string();
+                }
+            }
+            _ => ()
+        }
+        self
+    }
+}
+
+impl From<String> for Error {
+    fn from(message: String) -> Error {
+        Error::runtime(message)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            Error::Lex{ref span, ref message} => {
+                write!(f, "LEXING FAILURE at {} {}\n{}\n{}",
+                       span,
+                       message,
+                       span.line_string,
+                       get_caret_string(span.col))
+            }
+            Error::Parse{ref span, ref message, ref hint} => {
+                try!(write!(f, "PARSING FAILURE at {} {}", span, message));
+                try!(span.write_source_line(f));
+                match *hint {
+                    Some(ref h) => write!(f, "\n\nHint: {}\n", h),
+                    None => Ok(())
+                }
+            }
+            Error::Codegen{ref span, ref message} => {
+                try!(write!(f, "CODEGEN FAILURE at {}: {}", span, message));
+                span.write_source_line(f)
+            }
+            Error::Runtime{ref span, ref message, ref trace, ..} => {
+                match *span {
+                    Some(ref s) => {
+                        try!(write!(f, "EXECUTION FAILURE at {}: {}", s, message));
+                        try!(s.write_source_line(f));
+                    }
+                    None => try!(write!(f, "EXECUTION FAILURE: {}", message))
+                }
+                if trace.len() > 0 {
+                    try!(write!(f, "\nStack trace:"));
+                    for s in trace.iter() {
+                        try!(write!(f, "\n    played at {}", s));
+                    }
+                }
+                Ok(())
+            }
+            Error::Io{ref span, ref message} => {
+                match *span {
+                    Some(ref s) => write!(f, "I/O FAILURE at {}: {}", s, message),
+                    None => write!(f, "I/O FAILURE: {}", message)
+                }
+            }
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn description(&self) -> &str {
+        self.message()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Error, Span};
+
+    #[test]
+    fn test_display_runtime_error() {
+        let err = Error::Runtime {
+            span: Some(Span::new("test.hc", 1, 7, "  print(1, 2)")),
+            message: "Wrong number of args for `print`".to_string(),
+            value: None,
+            trace: vec![Span::new("test.hc", 3, 1, "f()")]
+        };
+        assert_eq!(
+            err.to_string(),
+            "EXECUTION FAILURE at test.hc:2:8: Wrong number of args for `print`\n\
+             2:   print(1, 2)\n\
+             \x20         ^\n\
+             Stack trace:\n\
+             \x20   played at test.hc:4:2"
+        );
+        assert_eq!(Error::runtime("oops").to_string(), "EXECUTION FAILURE: oops");
+    }
+
+    #[test]
+    fn test_in_file() {
+        let err = Error::Codegen {
+            span: Span::new("", 0, 4, "def 3: 4"),
+            message: "cannot def without bare item".to_string()
+        }.in_file("test.hc");
+        assert_eq!(err.span().unwrap().file, "test.hc");
+        assert_eq!(
+            err.to_string(),
+            "CODEGEN FAILURE at test.hc:1:5: cannot def without bare item\n1: def 3: 4\n       ^"
+        );
+    }
+}
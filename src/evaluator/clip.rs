@@ -1,17 +1,34 @@
 use std::cmp::Eq;
 use std::fmt::Debug;
 use std::rc::Rc;
-use std::cell::{RefCell, RefMut};
+use std::cell::{Ref, RefCell, RefMut};
 use std::hash::{Hash, Hasher};
 use std::mem;
 
 use super::value::Value;
 use super::environment::Environment;
+use error::Error;
 
 pub trait Clip: Debug {
     fn get(&self, &Value) -> Value;
-    fn set(&mut self, Value, Value) -> Result<(), String>;
-    fn play(&mut self, Vec<Value>, &mut Environment) -> Result<Value, String>;
+    fn set(&mut self, Value, Value) -> Result<(), Error>;
+    fn contains(&self, &Value) -> bool;
+    fn play(&mut self, Vec<Value>, &mut Environment) -> Result<Value, Error>;
+    //Native clips can hand `for` loops a separate iterator with a `next` def to loop over,
+    //which takes priority over `iter` and `next` defs
+    fn iter(&self) -> Option<Value> { None }
+    //Keys that `keys`, `values` and `items` go through
+    fn keys(&self) -> Vec<Value> { Vec::new() }
+    //Independent copy of the clip for `clone`. Clips that can't be copied without sharing
+    //state with the original give back None.
+    fn copy(&self) -> Option<Box<Clip>> { None }
+    //Clip to look up keys in when this one doesn't have them
+    fn proto(&self) -> Option<ClipHolder> { None }
+    //Items of clips that act as lists, which get shown like `[a, b]` instead of by their defs
+    fn list_items(&self) -> Option<Vec<Value>> { None }
+    //How clips that are meant to be played or that wrap something native get shown, like
+    //`fn(a, b) -> c`
+    fn signature(&self) -> Option<String> { None }
 }
 
 #[derive(Clone, Debug)]
@@ -23,16 +40,13 @@ impl Eq for ClipHolder {}
 
 impl Hash for ClipHolder {
     fn hash<H: Hasher>(&self, state: &mut H) {
-        let self_ptr: usize = unsafe {mem::transmute(&self.clip.borrow())};
-        self_ptr.hash(state);
+        self.id().hash(state);
     }
 }
 
 impl PartialEq for ClipHolder {
     fn eq(&self, other: &ClipHolder) -> bool {
-        let self_ptr: usize = unsafe {mem::transmute(self.clip.as_unsafe_cell().get())};
-        let other_ptr: usize = unsafe {mem::transmute(other.clip.as_unsafe_cell().get())};
-        self_ptr == other_ptr
+        self.id() == other.id()
     }
 
     fn ne(&self, other: &ClipHolder) -> bool {
@@ -47,7 +61,86 @@ impl ClipHolder {
         }
     }
 
+    //Identifies the clip itself, so that copies of the same holder are equal
+    pub fn id(&self) -> usize {
+        unsafe {mem::transmute(self.clip.as_unsafe_cell().get())}
+    }
+
+    pub fn borrow(&self) -> Ref<Box<Clip>> {
+        self.clip.borrow()
+    }
+
     pub fn borrow_mut(&mut self) -> RefMut<Box<Clip>> {
         self.clip.borrow_mut()
     }
+
+    //None while the clip is being played, since it's mutably borrowed for the whole play
+    pub fn try_borrow(&self) -> Option<Ref<Box<Clip>>> {
+        self.clip.try_borrow().ok()
+    }
+
+    //Looks up a key, falling through to the prototype chain if the clip doesn't have it
+    pub fn get(&self, key: &Value) -> Value {
+        let mut cur = self.clone();
+        loop {
+            let proto = {
+                let clip = cur.clip.borrow();
+                let value = clip.get(key);
+                if value != Value::Nil {
+                    return value;
+                }
+                match clip.proto() {
+                    Some(p) => p,
+                    None => {return Value::Nil;}
+                }
+            };
+            cur = proto;
+        }
+    }
+
+    pub fn contains(&self, key: &Value) -> bool {
+        let mut cur = self.clone();
+        loop {
+            let proto = {
+                let clip = cur.clip.borrow();
+                if clip.contains(key) {
+                    return true;
+                }
+                match clip.proto() {
+                    Some(p) => p,
+                    None => {return false;}
+                }
+            };
+            cur = proto;
+        }
+    }
+
+    //Sets a key on the clip itself, never on its prototypes
+    pub fn set(&self, key: Value, value: Value) -> Result<(), Error> {
+        if key == Value::String("proto".to_string()) {
+            if let Value::Clip(ref proto) = value {
+                try!(check_proto(self.id(), proto));
+            }
+        }
+        self.clip.borrow_mut().set(key, value)
+    }
+
+}
+
+//Makes sure the clip with the given id doesn't inherit from the proto it's being given, since
+//that would make a cycle. Every `proto` that gets set goes through here, so the chains stay
+//short of cycles and lookups don't have to look out for them.
+pub fn check_proto(id: usize, proto: &ClipHolder) -> Result<(), Error> {
+    let mut cur = Some(proto.clone());
+    while let Some(c) = cur {
+        if c.id() == id {
+            return Err(Error::runtime("can't set `proto` to a clip that inherits from this one"));
+        }
+        //Clips that are being played can't be looked into
+        cur = match c.try_borrow() {
+            Some(clip) => clip.proto(),
+            None => None
+        };
+    }
+    Ok(())
 }
@@ -1,15 +1,127 @@
+use std::ops::{Index, IndexMut};
+use std::rc::Rc;
+
 use ast::*;
 use super::ops::{Op, ClipParts};
 use super::value::{Value, FloatWrap};
+use super::source_map::{SourceFile, SourceMap, SourcePos};
+use error::Error;
+
+//Ops being generated, along with the source position that each one came from
+pub struct OpList {
+    ops: Vec<Op>,
+    positions: Vec<SourcePos>,
+    file: Rc<SourceFile>,
+    cur_pos: SourcePos
+}
+
+impl OpList {
+    pub fn new(file: Rc<SourceFile>) -> OpList {
+        OpList {
+            ops: Vec::with_capacity(1024),
+            positions: Vec::with_capacity(1024),
+            file: file,
+            cur_pos: SourcePos{line: 0, col: 0}
+        }
+    }
+
+    pub fn push(&mut self, op: Op) {
+        self.ops.push(op);
+        self.positions.push(self.cur_pos);
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.len()
+    }
+
+    //Sets the position used for pushed ops, returning the previous one so that it can be restored
+    fn set_pos(&mut self, data: &AstData) -> SourcePos {
+        let prev_pos = self.cur_pos;
+        self.cur_pos = SourcePos{line: data.line, col: data.col};
+        prev_pos
+    }
+
+    fn restore_pos(&mut self, pos: SourcePos) {
+        self.cur_pos = pos;
+    }
 
+    pub fn into_parts(self) -> (Vec<Op>, SourceMap) {
+        (self.ops, SourceMap::new(self.file, self.positions))
+    }
+}
+
+impl Index<usize> for OpList {
+    type Output = Op;
+
+    fn index(&self, idx: usize) -> &Op {
+        &self.ops[idx]
+    }
+}
+
+impl IndexMut<usize> for OpList {
+    fn index_mut(&mut self, idx: usize) -> &mut Op {
+        &mut self.ops[idx]
+    }
+}
 
-fn codegen_failure<T>(line_number: usize, message: &str) -> Result<T, String> {
-    Err(format!("CODEGEN FAILURE at line {}: {}", line_number + 1, message))
+
+//Jump locations for the innermost loop that `break` and `continue` refer to
+struct LoopJumps {
+    continue_jump_idx: usize,
+    //Indices of jumps that need to be patched to point past the end of the loop
+    break_jumps: Vec<usize>,
+    //Number of scopes pushed inside the loop body that need to be popped when leaving early
+    scope_depth: usize,
+    //Number of `try` blocks entered inside the loop body, whose handlers need popping too
+    handler_depth: usize
+}
+
+impl LoopJumps {
+    fn new(continue_jump_idx: usize) -> LoopJumps {
+        LoopJumps {
+            continue_jump_idx: continue_jump_idx,
+            break_jumps: Vec::new(),
+            scope_depth: 0,
+            handler_depth: 0
+        }
+    }
 }
 
-fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut Vec<Op>) -> Result<(), String> {
+fn codegen_failure<T>(file: &SourceFile, data: &AstData, message: &str) -> Result<T, Error> {
+    Err(Error::Codegen {
+        span: file.span(data.line, data.col),
+        message: message.to_string()
+    })
+}
+
+fn gen_postfix<'a>(postfix: &'a Postfix<'a>, ops: &mut OpList) -> Result<(), Error> {
+    match postfix {
+        &Postfix::Play(ref params) => {
+            for expr in params.iter().rev() {
+                try!(gen_expr(expr, ops));
+            }
+            ops.push(Op::Play(params.len()));
+        }
+        &Postfix::PlaySelf(ref ident, ref params) => {
+            ops.push(Op::Access(Box::new(Value::String(ident.to_string()))));
+            for expr in params.iter().rev() {
+                try!(gen_expr(expr, ops));
+            }
+            ops.push(Op::PlaySelf(params.len()));
+        }
+        &Postfix::Index(ref expr) => {
+            try!(gen_expr(expr, ops));
+            ops.push(Op::GetAndAccess);
+        }
+        &Postfix::Access(ref s) => ops.push(Op::AccessPop(Box::new(Value::String(s.to_string()))))
+    }
+    Ok(())
+}
+
+fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut OpList) -> Result<(), Error> {
     let &Expr{ref expr, ref data} = expr;
-    match expr {
+    let outer_pos = ops.set_pos(data);
+    let result = match expr {
         &ExprType::UnOp{ref expr, ref op, ..} => {
             try!(gen_expr(expr, ops));
             let new_op = match op {
@@ -29,6 +141,7 @@ fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut Vec<Op>) -> Result<(), String> {
                 &BinOp::Mul => Op::Mul,
                 &BinOp::Div => Op::Div,
                 &BinOp::Mod => Op::Mod,
+                &BinOp::Exp => Op::Pow,
                 &BinOp::In => Op::In,
                 &BinOp::Lt => Op::Lt,
                 &BinOp::Lte => Op::Lte,
@@ -37,8 +150,7 @@ fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut Vec<Op>) -> Result<(), String> {
                 &BinOp::Eq => Op::Eq,
                 &BinOp::Neq => Op::Neq,
                 &BinOp::And => Op::And,
-                &BinOp::Or => Op::Or,
-                _ => {return codegen_failure(data.line, "binary op not implemented");}
+                &BinOp::Or => Op::Or
             };
             ops.push(new_op);
             Ok(())
@@ -47,16 +159,19 @@ fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut Vec<Op>) -> Result<(), String> {
             match value {
                 &Literal::Bool(b) => ops.push(Op::Push(Box::new(Value::Bool(b)))),
                 &Literal::Int(i) => ops.push(Op::Push(Box::new(Value::Int(i)))),
+                &Literal::BigInt(n) => ops.push(Op::Push(Box::new(Value::BigInt(n.clone())))),
                 &Literal::Float(f) => ops.push(Op::Push(Box::new(Value::Float(FloatWrap::new(f))))),
                 &Literal::String(s) => ops.push(Op::Push(Box::new(Value::String(s.to_string())))),
                 &Literal::Nil => ops.push(Op::Push(Box::new(Value::Nil))),
                 &Literal::Clip{ref params, ref returns, ref statements} => {
-                    let mut func_ops = Vec::new();
+                    let mut func_ops = OpList::new(ops.file.clone());
                     try!(gen_stmt_list(statements, &mut func_ops));
+                    let (func_ops, source_map) = func_ops.into_parts();
                     ops.push(Op::PushClip(ClipParts{
                         params: params.iter().map(|p| p.to_string()).collect(),
                         returns: returns.iter().map(|r| r.to_string()).collect(),
-                        ops: func_ops
+                        ops: func_ops,
+                        source_map: source_map
                     }));
                 }
             }
@@ -69,26 +184,7 @@ fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut Vec<Op>) -> Result<(), String> {
         &ExprType::Postfix{ref expr, ref postfixes, ..} => {
             try!(gen_expr(expr, ops));
             for postfix in postfixes.iter() {
-                match postfix {
-                    &Postfix::Play(ref params) => {
-                        for expr in params.iter().rev() {
-                            try!(gen_expr(expr, ops));
-                        }
-                        ops.push(Op::Play(params.len()));
-                    }
-                    &Postfix::PlaySelf(ref ident, ref params) => {
-                        ops.push(Op::Access(Box::new(Value::String(ident.to_string()))));
-                        for expr in params.iter().rev() {
-                            try!(gen_expr(expr, ops));
-                        }
-                        ops.push(Op::PlaySelf(params.len()));
-                    }
-                    &Postfix::Index(ref expr) => {
-                        try!(gen_expr(expr, ops));
-                        ops.push(Op::GetAndAccess);
-                    }
-                    &Postfix::Access(ref s) => ops.push(Op::AccessPop(Box::new(Value::String(s.to_string()))))
-                }
+                try!(gen_postfix(postfix, ops));
             }
             Ok(())
         }
@@ -102,49 +198,69 @@ fn gen_expr<'a>(expr: &'a Expr<'a>, ops: &mut Vec<Op>) -> Result<(), String> {
             }
             Ok(())
         }
-    }
+        &ExprType::List{ref values, ..} => {
+            for expr in values.iter().rev() {
+                try!(gen_expr(expr, ops));
+            }
+            ops.push(Op::MakeList(values.len()));
+            Ok(())
+        }
+        &ExprType::Interpolate{ref parts, ..} => {
+            for (idx, part) in parts.iter().enumerate() {
+                try!(gen_expr(part, ops));
+                match part.expr {
+                    ExprType::Literal{value: Literal::String(_)} => (),
+                    _ => ops.push(Op::ToStr)
+                }
+                if idx > 0 {
+                    ops.push(Op::Add);
+                }
+            }
+            Ok(())
+        }
+    };
+    ops.restore_pos(outer_pos);
+    result
 }
 
-fn eval_expr_as_ident_values<'a>(expr: &'a Expr) -> Result<Vec<&'a str>, String> {
-    let &Expr{ref expr, ref data} = expr;
+//Stores the value on top of the stack into an ident or a path like `a.b[k]`. Idents are
+//stored as vars when assigning and as defs on the current clip when defining.
+fn gen_store<'a>(target: &'a Expr<'a>, ops: &mut OpList, is_def: bool) -> Result<(), Error> {
+    let &Expr{ref expr, ref data} = target;
     match expr {
-        &ExprType::Ident{name, ..} => Ok(vec![name]),
-        //TODO: implement idents for defining interior values
+        &ExprType::Ident{name, ..} => {
+            if is_def {
+                ops.push(Op::DefSelf(Box::new(Value::String(name.to_string()))));
+            } else {
+                ops.push(Op::Store(name.to_string()));
+            }
+            Ok(())
+        }
         &ExprType::Postfix{ref expr, ref postfixes} => {
-            let mut result_vec = Vec::new();
-            match **expr {
-                Expr{expr: ExprType::Ident{name, ..}, ..} => {
-                    result_vec.push(name);
-                }
-                _ => {return codegen_failure(data.line, "cannot assign to a non-ident");}
+            //Everything but the last postfix gets the clip that's being stored into
+            let (last, init) = postfixes.split_last().unwrap();
+            try!(gen_expr(expr, ops));
+            for postfix in init.iter() {
+                try!(gen_postfix(postfix, ops));
             }
-            for postfix in postfixes.iter() {
-                match postfix {
-                    &Postfix::Access(s) => {
-                        result_vec.push(s);
-                    }
-                    //TODO: need to do this for index types, too
-                    _ => {return codegen_failure(data.line, "cannot assign to a non-ident");}
+            match last {
+                &Postfix::Access(s) => ops.push(Op::Def(Box::new(Value::String(s.to_string())))),
+                &Postfix::Index(ref key) => {
+                    try!(gen_expr(key, ops));
+                    ops.push(Op::StoreIndex);
                 }
+                _ => {return codegen_failure(&ops.file, data, "cannot assign to the result of playing a clip");}
             }
-            Ok(result_vec)
+            Ok(())
         }
-        _ => codegen_failure(data.line, "cannot assign to a non-ident")
-    }
-}
-
-
-fn eval_expr_as_ident_str<'a>(expr: &'a Expr) -> Result<&'a str, String> {
-    let &Expr{ref expr, ref data} = expr;
-    match expr {
-        &ExprType::Ident{name, ..} => Ok(name),
-        _ => codegen_failure(data.line, "not an ident type")
+        _ => codegen_failure(&ops.file, data, "cannot assign to expression")
     }
 }
 
-fn gen_stmt<'a>(stmt: &'a Stmt, ops: &mut Vec<Op>) -> Result<(), String> {
+fn gen_stmt<'a>(stmt: &'a Stmt, ops: &mut OpList, loops: &mut Vec<LoopJumps>) -> Result<(), Error> {
     let &Stmt{ref stmt, ref data} = stmt;
-    match stmt {
+    let outer_pos = ops.set_pos(data);
+    let result = match stmt {
         &StmtType::Assign{ref items, ref expr, ..} => {
             try!(gen_expr(expr, ops));
             if items.len() > 1 {
@@ -158,43 +274,25 @@ fn gen_stmt<'a>(stmt: &'a Stmt, ops: &mut Vec<Op>) -> Result<(), String> {
                     StmtItem::Let(s) => {
                         ops.push(Op::DeclareAndStoreImmutable(s.to_string()));
                     }
-                    StmtItem::Bare(ref expr) => {
-                        let key = try!(eval_expr_as_ident_str(expr));
-                        ops.push(Op::Store(key.to_string()));
-                    }
-                    StmtItem::Expr(_) => { return codegen_failure(data.line, "cannot assign to expression"); }
+                    StmtItem::Bare(ref expr) => try!(gen_store(expr, ops, false)),
+                    StmtItem::Expr(_) => { return codegen_failure(&ops.file, data, "cannot assign to expression"); }
                 }
             }
             Ok(())
         }
         &StmtType::Def{ref items, ref expr, ..} => {
-            let mut new_ops = Vec::new();
-            try!(gen_expr(expr, &mut new_ops));
-            ops.append(&mut new_ops);
+            try!(gen_expr(expr, ops));
             if items.len() > 1 {
                 ops.push(Op::ExpandTuple(items.len()));
             }
             for item in items.iter() {
                 match *item {
-                    StmtItem::Bare(ref expr) => {
-                        let mut keys = try!(eval_expr_as_ident_values(expr));
-                        let assign_key = keys.pop().unwrap();
-                        if keys.len() > 0 {
-                            let base_ident = keys.remove(0);
-                            ops.push(Op::Load(base_ident.to_string()));
-                            for ident in keys.into_iter() {
-                                ops.push(Op::Access(Box::new(Value::String(ident.to_string()))));
-                            }
-                            ops.push(Op::Def(Box::new(Value::String(assign_key.to_string()))));
-                        } else {
-                            ops.push(Op::DefSelf(Box::new(Value::String(assign_key.to_string()))));
-                        }
-                    }
+                    StmtItem::Bare(ref expr) => try!(gen_store(expr, ops, true)),
                     StmtItem::Expr(ref expr) => {
                         try!(gen_expr(expr, ops));
                         ops.push(Op::DefPop);
                     }
-                    _ => {return codegen_failure(data.line, "cannot def without bare item");}
+                    _ => {return codegen_failure(&ops.file, data, "cannot def without bare item");}
                 }
             }
             Ok(())
@@ -203,57 +301,40 @@ fn gen_stmt<'a>(stmt: &'a Stmt, ops: &mut Vec<Op>) -> Result<(), String> {
             for item in items.iter() {
                 match item {
                     &StmtItem::Bare(ref expr) => try!(gen_expr(expr, ops)),
-                    _ => {return codegen_failure(data.line, "cannot have a non-bare statement item in a bare statement");}
+                    _ => {return codegen_failure(&ops.file, data, "cannot have a non-bare statement item in a bare statement");}
                 }
             }
             Ok(())
         }
         &StmtType::If{ref clauses, ..} => {
-            let mut if_conditions = Vec::new();
-            let mut if_statements = Vec::new();
-            let mut else_ops = Vec::new();
+            let mut skip_else_jumps = Vec::new();
             for clause in clauses.iter() {
                 match clause {
                     &IfClause::If{ref condition, ref statements} => {
-                        let mut new_condition = Vec::new();
-                        let mut new_statements = Vec::new();
-                        new_statements.push(Op::PushScope);
-                        try!(gen_expr(condition, &mut new_condition));
-                        try!(gen_stmt_list(statements, &mut new_statements));
-                        new_statements.push(Op::PopScope);
-                        if_conditions.push(new_condition);
-                        if_statements.push(new_statements);
+                        try!(gen_expr(condition, ops));
+                        //Target is filled in once we know where the clause ends
+                        let false_jump_idx = ops.len();
+                        ops.push(Op::JumpIfFalse(0));
+                        try!(gen_scoped_stmt_list(statements, ops, loops));
+                        //Got a true value, skip over the other clauses
+                        skip_else_jumps.push(ops.len());
+                        ops.push(Op::Jump(0));
+                        //If false, jump here
+                        ops[false_jump_idx] = Op::JumpIfFalse(ops.len());
+                        ops.push(Op::JumpTarget);
                     }
                     &IfClause::Else(ref statements) => {
-                        else_ops.push(Op::PushScope);
-                        try!(gen_stmt_list(statements, &mut else_ops));
-                        else_ops.push(Op::PopScope);
+                        try!(gen_scoped_stmt_list(statements, ops, loops));
                         break;
                     }
                 }
             }
             //If any cases succeed, jump here
-            if else_ops.len() > 0 {
-                else_ops.push(Op::JumpTarget);
-            }
-            let mut skip_else_target = ops.len() + else_ops.len() - 1;
-            for (cond, stmts) in if_statements.iter().zip(if_conditions.iter()) {
-                //Add 1 for the jumps we're going to add
-                skip_else_target += cond.len() + stmts.len() + 3;
-            }
-            for (mut stmts, mut cond) in if_statements.into_iter().zip(if_conditions.into_iter()) {
-                //Jump here if false
-                let false_target = ops.len() + cond.len() + stmts.len() + 2;
-                //println!("cond is {:?}", &cond);
-                ops.append(&mut cond);
-                ops.push(Op::JumpIfFalse(false_target));
-                ops.append(&mut stmts);
-                //Got a true value, skip over the other clauses
-                ops.push(Op::Jump(skip_else_target));
-                //If false, jump here
-                ops.push(Op::JumpTarget);
+            let skip_else_target = ops.len();
+            ops.push(Op::JumpTarget);
+            for idx in skip_else_jumps.into_iter() {
+                ops[idx] = Op::Jump(skip_else_target);
             }
-            ops.append(&mut else_ops);
             Ok(())
         }
         &StmtType::While{ref condition, ref statements, ..} => {
@@ -261,15 +342,20 @@ fn gen_stmt<'a>(stmt: &'a Stmt, ops: &mut Vec<Op>) -> Result<(), String> {
             let continue_jump_idx = ops.len();
             ops.push(Op::JumpTarget);
             try!(gen_expr(condition, ops));
-            let mut body_ops = Vec::new();
-            try!(gen_stmt_list(statements, &mut body_ops));
-            //JumpIfFalse to the jump target after the statement list and the continue jump
-            let break_jump_idx = ops.len() + body_ops.len() + 2;
-            ops.push(Op::JumpIfFalse(break_jump_idx));
-            ops.append(&mut body_ops);
+            let cond_jump_idx = ops.len();
+            ops.push(Op::JumpIfFalse(0));
+            loops.push(LoopJumps::new(continue_jump_idx));
+            try!(gen_stmt_block(statements, ops, loops));
+            let loop_jumps = loops.pop().unwrap();
             //Jump back to the beginning to continue
             ops.push(Op::Jump(continue_jump_idx));
+            //JumpIfFalse and breaks go to the jump target after the continue jump
+            let break_jump_idx = ops.len();
             ops.push(Op::JumpTarget);
+            ops[cond_jump_idx] = Op::JumpIfFalse(break_jump_idx);
+            for idx in loop_jumps.break_jumps.into_iter() {
+                ops[idx] = Op::Jump(break_jump_idx);
+            }
             Ok(())
         }
         &StmtType::For{ref idents, ref iterator, ref statements, ..} => {
@@ -288,32 +374,127 @@ fn gen_stmt<'a>(stmt: &'a Stmt, ops: &mut Vec<Op>) -> Result<(), String> {
             //Compares with right half of tuple
             ops.push(Op::Push(Box::new(Value::Bool(true))));
             ops.push(Op::Eq);
-            let mut body_ops = Vec::new();
+            let cond_jump_idx = ops.len();
+            ops.push(Op::JumpIfFalse(0));
             if idents.len() > 1 {
-                body_ops.push(Op::ExpandTuple(idents.len()));
+                ops.push(Op::ExpandTuple(idents.len()));
             }
             for ident in idents.iter() {
-                body_ops.push(Op::DeclareAndStore(ident.to_string()));
+                ops.push(Op::DeclareAndStore(ident.to_string()));
             }
-            try!(gen_stmt_list(statements, &mut body_ops));
-            //JumpIfFalse to the jump target after the statement list and the continue jump
-            let break_jump_idx = ops.len() + body_ops.len() + 2;
-            ops.push(Op::JumpIfFalse(break_jump_idx));
-            ops.append(&mut body_ops);
+            loops.push(LoopJumps::new(continue_jump_idx));
+            try!(gen_stmt_block(statements, ops, loops));
+            let loop_jumps = loops.pop().unwrap();
             //Jump back to the beginning to continue
             ops.push(Op::Jump(continue_jump_idx));
+            //JumpIfFalse and breaks go to the jump target after the continue jump,
+            //which cleans up the iterator and the loop's scope
+            let break_jump_idx = ops.len();
             ops.push(Op::JumpTarget);
             ops.push(Op::PopIterator);
             ops.push(Op::PopScope);
+            ops[cond_jump_idx] = Op::JumpIfFalse(break_jump_idx);
+            for idx in loop_jumps.break_jumps.into_iter() {
+                ops[idx] = Op::Jump(break_jump_idx);
+            }
             Ok(())
         }
+        &StmtType::Try{ref statements, ref ident, ref catch_statements, ..} => {
+            //If anything in the block fails, the VM jumps to the catch target with the error on
+            //the stack
+            let handler_idx = ops.len();
+            ops.push(Op::PushHandler(0));
+            if let Some(loop_jumps) = loops.last_mut() {
+                loop_jumps.handler_depth += 1;
+            }
+            try!(gen_scoped_stmt_list(statements, ops, loops));
+            if let Some(loop_jumps) = loops.last_mut() {
+                loop_jumps.handler_depth -= 1;
+            }
+            ops.push(Op::PopHandler);
+            let skip_catch_idx = ops.len();
+            ops.push(Op::Jump(0));
+            let catch_target = ops.len();
+            ops.push(Op::JumpTarget);
+            ops.push(Op::PushScope);
+            if let Some(loop_jumps) = loops.last_mut() {
+                loop_jumps.scope_depth += 1;
+            }
+            ops.push(Op::DeclareAndStore(ident.to_string()));
+            try!(gen_stmt_block(catch_statements, ops, loops));
+            if let Some(loop_jumps) = loops.last_mut() {
+                loop_jumps.scope_depth -= 1;
+            }
+            ops.push(Op::PopScope);
+            let skip_catch_target = ops.len();
+            ops.push(Op::JumpTarget);
+            ops[handler_idx] = Op::PushHandler(catch_target);
+            ops[skip_catch_idx] = Op::Jump(skip_catch_target);
+            Ok(())
+        }
+        &StmtType::Break => {
+            match loops.last_mut() {
+                Some(loop_jumps) => {
+                    //Unwind any scopes opened since the start of the loop body
+                    for _ in 0..loop_jumps.scope_depth {
+                        ops.push(Op::PopScope);
+                    }
+                    for _ in 0..loop_jumps.handler_depth {
+                        ops.push(Op::PopHandler);
+                    }
+                    loop_jumps.break_jumps.push(ops.len());
+                    ops.push(Op::Jump(0));
+                    Ok(())
+                }
+                None => codegen_failure(&ops.file, data, "`break` must be inside of a loop")
+            }
+        }
+        &StmtType::Continue => {
+            match loops.last() {
+                Some(loop_jumps) => {
+                    //Unwind any scopes opened since the start of the loop body
+                    for _ in 0..loop_jumps.scope_depth {
+                        ops.push(Op::PopScope);
+                    }
+                    for _ in 0..loop_jumps.handler_depth {
+                        ops.push(Op::PopHandler);
+                    }
+                    ops.push(Op::Jump(loop_jumps.continue_jump_idx));
+                    Ok(())
+                }
+                None => codegen_failure(&ops.file, data, "`continue` must be inside of a loop")
+            }
+        }
         &StmtType::Return => {ops.push(Op::Return); Ok(())}
-    }
+    };
+    ops.restore_pos(outer_pos);
+    result
 }
 
-pub fn gen_stmt_list<'a>(statements: &'a Vec<Stmt<'a>>, ops: &mut Vec<Op>) -> Result<(), String> {
+fn gen_stmt_block<'a>(statements: &'a Vec<Stmt<'a>>, ops: &mut OpList,
+                      loops: &mut Vec<LoopJumps>) -> Result<(), Error> {
     for statement in statements.iter() {
-        try!(gen_stmt(statement, ops));
+        try!(gen_stmt(statement, ops, loops));
     }
     Ok(())
 }
+
+fn gen_scoped_stmt_list<'a>(statements: &'a Vec<Stmt<'a>>, ops: &mut OpList,
+                            loops: &mut Vec<LoopJumps>) -> Result<(), Error> {
+    ops.push(Op::PushScope);
+    if let Some(loop_jumps) = loops.last_mut() {
+        loop_jumps.scope_depth += 1;
+    }
+    try!(gen_stmt_block(statements, ops, loops));
+    if let Some(loop_jumps) = loops.last_mut() {
+        loop_jumps.scope_depth -= 1;
+    }
+    ops.push(Op::PopScope);
+    Ok(())
+}
+
+pub fn gen_stmt_list<'a>(statements: &'a Vec<Stmt<'a>>, ops: &mut OpList) -> Result<(), Error> {
+    //Loops don't carry over into clip bodies, so each list starts without any
+    let mut loops = Vec::new();
+    gen_stmt_block(statements, ops, &mut loops)
+}
@@ -0,0 +1,184 @@
+use super::value::{Value, FloatWrap, big_to_f64};
+use error::Error;
+
+//Conversions between housecat values and Rust types, used when calling into native functions
+pub trait FromValue: Sized {
+    fn from_value(value: Value) -> Result<Self, Error>;
+}
+
+pub trait IntoValue {
+    fn into_value(self) -> Value;
+}
+
+fn type_error<T>(expected: &str, found: &Value) -> Result<T, Error> {
+    Err(Error::runtime(format!("expected {} but found {}", expected, found.type_name())))
+}
+
+impl FromValue for Value {
+    fn from_value(value: Value) -> Result<Value, Error> {
+        Ok(value)
+    }
+}
+
+impl FromValue for i64 {
+    fn from_value(value: Value) -> Result<i64, Error> {
+        match value {
+            Value::Int(i) => Ok(i),
+            Value::BigInt(n) => Err(Error::runtime(format!("{} is too big for a 64-bit int", n))),
+            v => type_error("int", &v)
+        }
+    }
+}
+
+//Ints are accepted where a float is expected, since there's no loss in the other direction
+impl FromValue for f64 {
+    fn from_value(value: Value) -> Result<f64, Error> {
+        match value {
+            Value::Float(f) => Ok(f.get()),
+            Value::Int(i) => Ok(i as f64),
+            Value::BigInt(n) => Ok(big_to_f64(&n)),
+            v => type_error("float", &v)
+        }
+    }
+}
+
+impl FromValue for bool {
+    fn from_value(value: Value) -> Result<bool, Error> {
+        match value {
+            Value::Bool(b) => Ok(b),
+            v => type_error("bool", &v)
+        }
+    }
+}
+
+impl FromValue for String {
+    fn from_value(value: Value) -> Result<String, Error> {
+        match value {
+            Value::String(s) => Ok(s),
+            v => type_error("string", &v)
+        }
+    }
+}
+
+//Tuples and lists both convert, item by item
+impl<T: FromValue> FromValue for Vec<T> {
+    fn from_value(value: Value) -> Result<Vec<T>, Error> {
+        let vals = match value {
+            Value::Tuple(vals) => vals,
+            Value::Clip(ref c) => {
+                match c.try_borrow().and_then(|c| c.list_items()) {
+                    Some(items) => items,
+                    None => {return type_error("tuple or list", &value);}
+                }
+            }
+            v => {return type_error("tuple or list", &v);}
+        };
+        let mut result = Vec::with_capacity(vals.len());
+        for v in vals.into_iter() {
+            result.push(try!(T::from_value(v)));
+        }
+        Ok(result)
+    }
+}
+
+//Nil becomes None, anything else has to convert to the inner type
+impl<T: FromValue> FromValue for Option<T> {
+    fn from_value(value: Value) -> Result<Option<T>, Error> {
+        match value {
+            Value::Nil => Ok(None),
+            v => Ok(Some(try!(T::from_value(v))))
+        }
+    }
+}
+
+impl IntoValue for Value {
+    fn into_value(self) -> Value {
+        self
+    }
+}
+
+impl IntoValue for () {
+    fn into_value(self) -> Value {
+        Value::Nil
+    }
+}
+
+impl IntoValue for i64 {
+    fn into_value(self) -> Value {
+        Value::Int(self)
+    }
+}
+
+impl IntoValue for f64 {
+    fn into_value(self) -> Value {
+        Value::Float(FloatWrap::new(self))
+    }
+}
+
+impl IntoValue for bool {
+    fn into_value(self) -> Value {
+        Value::Bool(self)
+    }
+}
+
+impl IntoValue for String {
+    fn into_value(self) -> Value {
+        Value::String(self)
+    }
+}
+
+impl<'a> IntoValue for &'a str {
+    fn into_value(self) -> Value {
+        Value::String(self.to_string())
+    }
+}
+
+impl<T: IntoValue> IntoValue for Vec<T> {
+    fn into_value(self) -> Value {
+        Value::Tuple(self.into_iter().map(|v| v.into_value()).collect())
+    }
+}
+
+impl<T: IntoValue> IntoValue for Option<T> {
+    fn into_value(self) -> Value {
+        match self {
+            Some(v) => v.into_value(),
+            None => Value::Nil
+        }
+    }
+}
+
+macro_rules! tuple_conversions {
+    ($len:expr, $($name:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($name: FromValue),+> FromValue for ($($name,)+) {
+            fn from_value(value: Value) -> Result<($($name,)+), Error> {
+                match value {
+                    Value::Tuple(vals) => {
+                        if vals.len() != $len {
+                            return Err(Error::runtime(format!(
+                                "expected tuple of length {} but found one of length {}",
+                                $len, vals.len())));
+                        }
+                        let mut iter = vals.into_iter();
+                        $(let $name = try!($name::from_value(iter.next().unwrap()));)+
+                        Ok(($($name,)+))
+                    }
+                    v => type_error("tuple", &v)
+                }
+            }
+        }
+
+        #[allow(non_snake_case)]
+        impl<$($name: IntoValue),+> IntoValue for ($($name,)+) {
+            fn into_value(self) -> Value {
+                let ($($name,)+) = self;
+                Value::Tuple(vec![$($name.into_value()),+])
+            }
+        }
+    }
+}
+
+tuple_conversions!(2, A, B);
+tuple_conversions!(3, A, B, C);
+tuple_conversions!(4, A, B, C, D);
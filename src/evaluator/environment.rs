@@ -1,4 +1,7 @@
 use super::value::Value;
+use super::clip::ClipHolder;
+use super::source_map::SourceLocation;
+use error::{Error, Span};
 use std::collections::HashMap;
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -36,13 +39,19 @@ pub enum RefType {
 }
 
 pub struct Environment {
-    defs: Vec<HashMap<String, EnvValue>>
+    defs: Vec<HashMap<String, EnvValue>>,
+    //Locations of the clip plays that are currently in progress
+    calls: Vec<SourceLocation>,
+    //Ids of the clips that are being played, innermost last
+    playing: Vec<usize>
 }
 
 impl Environment {
     pub fn new() -> Environment {
         Environment {
-            defs: Vec::new()
+            defs: Vec::new(),
+            calls: Vec::new(),
+            playing: Vec::new()
         }
     }
 
@@ -65,8 +74,9 @@ impl Environment {
                     return Err(format!("Cannot assign to {} because it is immutable", name));
                 }
                 Some(EnvValue::Plain(_)) => { scope.insert(name, EnvValue::Plain(value)); }
-                Some(EnvValue::Referenced(ref rv)) => {
+                Some(EnvValue::Referenced(rv)) => {
                     rv.borrow_mut().set(value);
+                    scope.insert(name, EnvValue::Referenced(rv));
                 }
                 _ => continue
             }
@@ -119,4 +129,65 @@ impl Environment {
     pub fn pop_frame(&mut self) {
         self.defs.pop();
     }
+
+    pub fn frame_depth(&self) -> usize {
+        self.defs.len()
+    }
+
+    //Drops any frames and plays left over from a failure, back to the given frame depth
+    pub fn unwind_to(&mut self, depth: usize) {
+        self.defs.truncate(depth);
+        self.calls.clear();
+    }
+
+    pub fn call_depth(&self) -> usize {
+        self.calls.len()
+    }
+
+    //Like `unwind_to` for a failure that gets caught, which keeps the plays that were already in
+    //progress when the catching began
+    pub fn restore(&mut self, depth: usize, call_depth: usize) {
+        self.defs.truncate(depth);
+        self.calls.truncate(call_depth);
+    }
+
+    pub fn push_call(&mut self, location: SourceLocation) {
+        self.calls.push(location);
+    }
+
+    pub fn pop_call(&mut self) {
+        self.calls.pop();
+    }
+
+    //Plays a clip in a frame of its own, recording where it was played from if that's known
+    //Whatever a failed play leaves behind gets dropped, so things are back where they were after
+    pub fn play(&mut self, clip: &mut ClipHolder, args: Vec<Value>, location: Option<SourceLocation>)
+                -> Result<Value, Error> {
+        let depth = self.frame_depth();
+        let call_depth = self.call_depth();
+        self.push_frame();
+        if let Some(location) = location {
+            self.push_call(location);
+        }
+        self.playing.push(clip.id());
+        let result = clip.borrow_mut().play(args, self);
+        self.playing.pop();
+        self.restore(depth, call_depth);
+        result
+    }
+
+    //Id of the clip whose code is running, which is the one its defs go to
+    pub fn playing_clip(&self) -> Option<usize> {
+        self.playing.last().cloned()
+    }
+
+    //Where the innermost play in progress was started from
+    pub fn current_call(&self) -> Option<SourceLocation> {
+        self.calls.last().cloned()
+    }
+
+    //Spans of the plays in progress, innermost first
+    pub fn call_trace(&self) -> Vec<Span> {
+        self.calls.iter().rev().map(|loc| loc.to_span()).collect()
+    }
 }
@@ -0,0 +1,29 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use super::value::Value;
+use super::clip::ClipHolder;
+use super::standard_clip::StdClip;
+use super::source_map::{SourceFile, SourceMap};
+use error::Error;
+
+//Clip that a caught error is turned into. `message` and `location` are strings (`location` is nil
+//if it isn't known), and `value` is whatever the script raised, or the message for VM errors.
+pub fn error_value(err: Error) -> Value {
+    let location = match err.span() {
+        Some(span) => Value::String(span.to_string()),
+        None => Value::Nil
+    };
+    let message = Value::String(err.message().to_string());
+    let value = match err {
+        Error::Runtime{value: Some(v), ..} => v,
+        _ => message.clone()
+    };
+    let mut defs = HashMap::new();
+    defs.insert(Value::String("message".to_string()), message);
+    defs.insert(Value::String("location".to_string()), location);
+    defs.insert(Value::String("value".to_string()), value);
+    let source_map = SourceMap::new(Rc::new(SourceFile::new("", "")), Vec::new());
+    let clip = StdClip::new_with_defs(Vec::new(), Vec::new(), Vec::new(), source_map, defs);
+    Value::Clip(ClipHolder::new(Box::new(clip)))
+}
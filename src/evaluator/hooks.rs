@@ -0,0 +1,129 @@
+use super::value::Value;
+use super::clip::ClipHolder;
+use super::environment::Environment;
+use super::iter_clip::sorted_defs;
+use error::Error;
+
+//Gets a hook like `__add` from a clip, if the value is a clip that has one
+pub fn get_hook(value: &Value, name: &str) -> Option<ClipHolder> {
+    match *value {
+        //Clips that are being played can't be looked into, so they don't have hooks
+        Value::Clip(ref c) if c.try_borrow().is_none() => None,
+        Value::Clip(ref c) => {
+            match c.get(&Value::String(name.to_string())) {
+                Value::Clip(hook) => Some(hook),
+                _ => None
+            }
+        }
+        _ => None
+    }
+}
+
+//Clip that gets played when a value is played, which is its `__call` hook with the value as the
+//first arg if it has one
+pub fn callee(value: Value, mut args: Vec<Value>) -> Option<(ClipHolder, Vec<Value>)> {
+    if let Some(hook) = get_hook(&value, "__call") {
+        args.insert(0, value);
+        return Some((hook, args));
+    }
+    match value {
+        Value::Clip(c) => Some((c, args)),
+        _ => None
+    }
+}
+
+//Plays a clip's `__str` hook, if it has one
+fn str_hook(value: &Value, environment: &mut Environment) -> Result<Option<String>, Error> {
+    match get_hook(value, "__str") {
+        Some(mut hook) => {
+            match try!(environment.play(&mut hook, vec![value.clone()], None)) {
+                Value::String(s) => Ok(Some(s)),
+                v => Err(Error::runtime(format!("`__str` must return a string, not {}", v.type_name())))
+            }
+        }
+        None => Ok(None)
+    }
+}
+
+//Keys that could be written as `key: value` in a clip literal don't need the `@`
+fn is_ident(s: &str) -> bool {
+    match s.chars().next() {
+        Some(c) if c.is_alphabetic() || c == '_' => s.chars().all(|c| c.is_alphanumeric() || c == '_'),
+        _ => false
+    }
+}
+
+fn render_all(values: &[Value], quote: bool, seen: &mut Vec<usize>,
+              hook: &mut FnMut(&Value) -> Result<Option<String>, Error>) -> Result<String, Error> {
+    let mut strings = Vec::with_capacity(values.len());
+    for v in values.iter() {
+        strings.push(try!(render(v, quote, seen, hook)));
+    }
+    Ok(strings.join(", "))
+}
+
+//Strings are quoted if `quote` is set, and always are inside of clips. Clips already being shown
+//are tracked by id, so a clip that contains itself shows up as `${...}`.
+fn render(value: &Value, quote: bool, seen: &mut Vec<usize>,
+          hook: &mut FnMut(&Value) -> Result<Option<String>, Error>) -> Result<String, Error> {
+    if let Some(s) = try!(hook(value)) {
+        return Ok(s);
+    }
+    match *value {
+        Value::String(ref s) if quote => Ok(format!("{:?}", s)),
+        Value::Tuple(ref vals) => Ok(format!("({})", try!(render_all(vals, quote, seen, hook)))),
+        Value::Clip(ref c) => {
+            if seen.contains(&c.id()) {
+                return Ok("${...}".to_string());
+            }
+            seen.push(c.id());
+            let result = render_clip(c, seen, hook);
+            seen.pop();
+            result
+        }
+        ref v => Ok(v.to_string())
+    }
+}
+
+//Lists show their items, clips that take args or give back results show their signature, and
+//anything else shows its defs in key order
+fn render_clip(clip: &ClipHolder, seen: &mut Vec<usize>,
+               hook: &mut FnMut(&Value) -> Result<Option<String>, Error>) -> Result<String, Error> {
+    //Clips that are being played can't be looked into
+    let (list_items, signature) = match clip.try_borrow() {
+        Some(c) => (c.list_items(), c.signature()),
+        None => {return Ok("${...}".to_string());}
+    };
+    if let Some(items) = list_items {
+        return Ok(format!("[{}]", try!(render_all(&items[..], true, seen, hook))));
+    }
+    let mut strings = Vec::new();
+    for (k, v) in sorted_defs(clip).into_iter() {
+        let key = match k {
+            Value::String(ref s) if is_ident(&s[..]) => s.clone(),
+            ref k => format!("@ {}", try!(render(k, true, seen, hook)))
+        };
+        strings.push(format!("{}: {}", key, try!(render(&v, true, seen, hook))));
+    }
+    let defs = format!("${{{}}}", strings.join(", "));
+    match signature {
+        Some(ref sig) if strings.is_empty() => Ok(sig.clone()),
+        Some(sig) => Ok(format!("{} {}", sig, defs)),
+        None => Ok(defs)
+    }
+}
+
+fn no_hook(_: &Value) -> Result<Option<String>, Error> {
+    Ok(None)
+}
+
+//String for a value as it's printed, using `__str` hooks for clips that have them
+pub fn display_string(value: &Value, environment: &mut Environment) -> Result<String, Error> {
+    render(value, false, &mut Vec::new(), &mut |v: &Value| str_hook(v, environment))
+}
+
+//String for a value as it would be written in code, with strings quoted and clips showing
+//their defs
+pub fn repr_string(value: &Value) -> String {
+    render(value, true, &mut Vec::new(), &mut no_hook).unwrap()
+}
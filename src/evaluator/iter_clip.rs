@@ -0,0 +1,204 @@
+use std::fmt::{Debug, Formatter};
+use std::fmt::Result as FmtResult;
+
+use super::value::Value;
+use super::clip::{Clip, ClipHolder};
+use super::environment::Environment;
+use error::Error;
+
+//Produces the next value of an iterator, or None once it's done. Gets the environment so that
+//it can play the `next` methods of script-defined iterators that it wraps.
+pub type IterStep = Box<FnMut(&mut Environment) -> Result<Option<Value>, Error>>;
+
+//Iterator clip whose `next` method is implemented in Rust, for use with `for` loops
+#[derive(Debug)]
+pub struct IterClip {
+    next: Value
+}
+
+impl IterClip {
+    pub fn new(step: IterStep) -> IterClip {
+        IterClip {
+            next: Value::Clip(ClipHolder::new(Box::new(IterNext{step: step})))
+        }
+    }
+
+    pub fn from_iter<I: Iterator<Item=Value> + 'static>(mut iter: I) -> IterClip {
+        IterClip::new(Box::new(move |_: &mut Environment| Ok(iter.next())))
+    }
+
+    pub fn into_value(self) -> Value {
+        Value::Clip(ClipHolder::new(Box::new(self)))
+    }
+}
+
+#[allow(unused_variables)]
+impl Clip for IterClip {
+    fn get(&self, key: &Value) -> Value {
+        match *key {
+            Value::String(ref s) if s == "next" => self.next.clone(),
+            _ => Value::Nil
+        }
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime("Cannot set a def on a native iterator"))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        match *key {
+            Value::String(ref s) => s == "next",
+            _ => false
+        }
+    }
+
+    fn play(&mut self, args: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
+        Err(Error::runtime("can't play a native iterator"))
+    }
+}
+
+struct IterNext {
+    step: IterStep
+}
+
+impl Debug for IterNext {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "IterNext")
+    }
+}
+
+#[allow(unused_variables)]
+impl Clip for IterNext {
+    fn get(&self, key: &Value) -> Value {
+        Value::Nil
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime("Cannot set a def on a native iterator's `next`"))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        false
+    }
+
+    fn play(&mut self, args: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
+        match try!((self.step)(environment)) {
+            Some(v) => Ok(Value::Tuple(vec![Value::Bool(true), v])),
+            None => Ok(Value::Tuple(vec![Value::Bool(false), Value::Nil]))
+        }
+    }
+}
+
+//Keys and values of a clip's defs, sorted by key so that loops over them always go in the
+//same order
+pub fn sorted_defs(clip: &ClipHolder) -> Vec<(Value, Value)> {
+    let mut keys = clip.borrow().keys();
+    keys.sort_by(|a, b| a.key_order(b));
+    keys.into_iter().map(|k| {
+        let v = clip.get(&k);
+        (k, v)
+    }).collect()
+}
+
+fn play_method(clip: &ClipHolder, name: &str, environment: &mut Environment)
+               -> Result<Value, Error> {
+    match clip.get(&Value::String(name.to_string())) {
+        Value::Clip(mut method) => environment.play(&mut method, vec![Value::Clip(clip.clone())], None),
+        v => {
+            Err(Error::runtime(format!("expected `{}` to be a clip, but it was {}",
+                                       name, v.type_name())))
+        }
+    }
+}
+
+fn has_def(clip: &ClipHolder, name: &str) -> bool {
+    clip.get(&Value::String(name.to_string())) != Value::Nil
+}
+
+//Gets something that follows the `next` protocol for a value that can be looped over. Clips can
+//hand out an iterator with a native hook or an `iter` method, be an iterator themselves with a
+//`next` method, or otherwise get looped over as (key, value) pairs.
+pub fn iterator_for(value: Value, environment: &mut Environment) -> Result<Value, Error> {
+    match value {
+        Value::Clip(c) => {
+            let native_iter = c.borrow().iter();
+            if let Some(iter) = native_iter {
+                Ok(iter)
+            } else if has_def(&c, "iter") {
+                //A clip that `iter` gives back is the iterator, anything else gets converted
+                match try!(play_method(&c, "iter", environment)) {
+                    Value::Clip(it) => {
+                        let native_iter = it.borrow().iter();
+                        Ok(native_iter.unwrap_or(Value::Clip(it)))
+                    }
+                    v => iterator_for(v, environment)
+                }
+            } else if has_def(&c, "next") {
+                Ok(Value::Clip(c))
+            } else {
+                let pairs = sorted_defs(&c).into_iter().map(|(k, v)| Value::Tuple(vec![k, v]));
+                Ok(IterClip::from_iter(pairs).into_value())
+            }
+        }
+        Value::Tuple(vals) => Ok(IterClip::from_iter(vals.into_iter()).into_value()),
+        Value::String(s) => {
+            let chars: Vec<Value> = s.chars().map(|c| Value::String(c.to_string())).collect();
+            Ok(IterClip::from_iter(chars.into_iter()).into_value())
+        }
+        v => Err(Error::runtime(format!("can't iterate over {}", v.type_name())))
+    }
+}
+
+//Iterator for a value that's only set up the first time it's stepped, for native clips that
+//don't have the environment when they're played but need it to play an `iter` method
+pub struct LazyIterator {
+    source: Option<Value>,
+    iterator: Value
+}
+
+impl LazyIterator {
+    pub fn new(value: Value) -> Result<LazyIterator, Error> {
+        match value {
+            Value::Clip(_) | Value::Tuple(_) | Value::String(_) => {
+                Ok(LazyIterator {
+                    source: Some(value),
+                    iterator: Value::Nil
+                })
+            }
+            v => Err(Error::runtime(format!("can't iterate over {}", v.type_name())))
+        }
+    }
+
+    pub fn next(&mut self, environment: &mut Environment) -> Result<Option<Value>, Error> {
+        if let Some(source) = self.source.take() {
+            self.iterator = try!(iterator_for(source, environment));
+        }
+        next_value(&self.iterator, environment)
+    }
+}
+
+//Plays an iterator's `next` method, the same way a `for` loop would
+pub fn next_value(iterator: &Value, environment: &mut Environment) -> Result<Option<Value>, Error> {
+    let next = match *iterator {
+        Value::Clip(ref c) => c.get(&Value::String("next".to_string())),
+        ref v => {return Err(Error::runtime(format!("can't iterate over {}", v.type_name())));}
+    };
+    let result = match next {
+        Value::Clip(mut n) => try!(environment.play(&mut n, vec![iterator.clone()], None)),
+        _ => {return Err(Error::runtime("iterators need a `next` method to play"));}
+    };
+    match result {
+        Value::Tuple(mut vals) => {
+            if vals.len() == 2 {
+                let v = vals.pop().unwrap();
+                match vals.pop().unwrap() {
+                    Value::Bool(true) => {return Ok(Some(v));}
+                    Value::Bool(false) => {return Ok(None);}
+                    _ => ()
+                }
+            }
+        }
+        _ => ()
+    }
+    Err(Error::runtime("`next` must return a (bool, value) tuple"))
+}
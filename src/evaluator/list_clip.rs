@@ -0,0 +1,144 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use super::value::Value;
+use super::clip::Clip;
+use super::environment::Environment;
+use super::native_clip::{NativeClip, native_value};
+use super::iter_clip::IterClip;
+use error::Error;
+
+//Growable sequence created by `[a, b, c]`. The items are shared with the method clips it hands
+//out, so `list|push(x)` changes the list itself.
+#[derive(Debug)]
+pub struct ListClip {
+    items: Rc<RefCell<Vec<Value>>>
+}
+
+//Negative indices count back from the end
+fn list_index(idx: i64, len: usize) -> Option<usize> {
+    let real_idx = if idx < 0 { idx + len as i64 } else { idx };
+    if real_idx >= 0 && (real_idx as usize) < len {
+        Some(real_idx as usize)
+    } else {
+        None
+    }
+}
+
+fn index_error<T>(idx: i64, len: usize) -> Result<T, Error> {
+    Err(Error::runtime(format!("index {} is out of range for a list of length {}", idx, len)))
+}
+
+impl ListClip {
+    pub fn new(items: Vec<Value>) -> ListClip {
+        ListClip {
+            items: Rc::new(RefCell::new(items))
+        }
+    }
+
+    fn method(&self, name: &str) -> Value {
+        let items = self.items.clone();
+        match name {
+            "len" => native_value(NativeClip::new(name, move |_: Value| {
+                items.borrow().len() as i64
+            })),
+            "push" => native_value(NativeClip::new(name, move |_: Value, value: Value| {
+                items.borrow_mut().push(value);
+            })),
+            "pop" => native_value(NativeClip::new(name, move |_: Value| {
+                match items.borrow_mut().pop() {
+                    Some(v) => Ok(v),
+                    None => Err(Error::runtime("can't pop from an empty list"))
+                }
+            })),
+            "insert" => native_value(NativeClip::new(name, move |_: Value, idx: i64, value: Value| {
+                let mut items = items.borrow_mut();
+                let len = items.len();
+                //Inserting at the length appends
+                match list_index(idx, len + 1) {
+                    Some(i) => {
+                        items.insert(i, value);
+                        Ok(())
+                    }
+                    None => index_error(idx, len)
+                }
+            })),
+            "remove" => native_value(NativeClip::new(name, move |_: Value, idx: i64| {
+                let mut items = items.borrow_mut();
+                let len = items.len();
+                match list_index(idx, len) {
+                    Some(i) => Ok(items.remove(i)),
+                    None => index_error(idx, len)
+                }
+            })),
+            _ => Value::Nil
+        }
+    }
+}
+
+impl Clip for ListClip {
+    fn get(&self, key: &Value) -> Value {
+        match *key {
+            Value::Int(idx) => {
+                let items = self.items.borrow();
+                match list_index(idx, items.len()) {
+                    Some(i) => items[i].clone(),
+                    None => Value::Nil
+                }
+            }
+            Value::String(ref name) => self.method(&name[..]),
+            _ => Value::Nil
+        }
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        match key {
+            Value::Int(idx) => {
+                let mut items = self.items.borrow_mut();
+                let len = items.len();
+                match list_index(idx, len) {
+                    Some(i) => {
+                        items[i] = value;
+                        Ok(())
+                    }
+                    None => index_error(idx, len)
+                }
+            }
+            k => Err(Error::runtime(format!("lists can only be indexed with ints, not {}", k.type_name())))
+        }
+    }
+
+    //Like tuples, `in` checks the items rather than the keys
+    fn contains(&self, key: &Value) -> bool {
+        self.items.borrow().contains(key)
+    }
+
+    #[allow(unused_variables)]
+    fn play(&mut self, args: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
+        Err(Error::runtime("can't play a list"))
+    }
+
+    //Each loop gets its own position, and sees items that are changed while it's running
+    fn iter(&self) -> Option<Value> {
+        let items = self.items.clone();
+        let mut pos = 0;
+        let iter = IterClip::new(Box::new(move |_: &mut Environment| {
+            let next = items.borrow().get(pos).cloned();
+            pos += 1;
+            Ok(next)
+        }));
+        Some(iter.into_value())
+    }
+
+    fn keys(&self) -> Vec<Value> {
+        (0..self.items.borrow().len()).map(|i| Value::Int(i as i64)).collect()
+    }
+
+    fn copy(&self) -> Option<Box<Clip>> {
+        Some(Box::new(ListClip::new(self.items.borrow().clone())))
+    }
+
+    fn list_items(&self) -> Option<Vec<Value>> {
+        Some(self.items.borrow().clone())
+    }
+}
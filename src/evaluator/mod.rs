@@ -5,51 +5,73 @@ pub mod standard_clip;
 pub mod value;
 pub mod clip;
 pub mod environment;
+pub mod source_map;
+pub mod convert;
+pub mod native_clip;
+pub mod list_clip;
+pub mod iter_clip;
+pub mod hooks;
+pub mod error_value;
 
-use ast::Stmt;
+use ast::{Stmt, StmtType};
 
-use self::codegen::gen_stmt_list;
+use self::codegen::{gen_stmt_list, OpList};
 use self::ops::Op;
 use self::vm::execute;
 use self::environment::Environment;
 use self::clip::ClipHolder;
 use self::value::Value;
+use self::source_map::{SourceFile, SourceMap};
 
 use std::collections::HashMap;
 use std::mem::size_of;
+use std::rc::Rc;
 
 use libhc::open_libs;
+use error::Error;
 
-fn print_debug_info(ops: &Vec<Op>) {
+fn print_debug_info(ops: &Vec<Op>, source_map: &SourceMap) {
     println!("running stack eval...");
     println!("op size: {}", size_of::<Op>());
     println!("value size: {}", size_of::<Value>());
     println!("boxed size: {}", size_of::<Box<Value>>());
     println!("string size: {}", size_of::<String>());
     for (idx, op) in ops.iter().enumerate() {
-        println!("{}: {:?}", idx, op);
+        println!("{} ({}): {:?}", idx, source_map.location(idx), op);
     }
 }
 
-pub fn evaluate<'a>(ast: &'a Vec<Stmt<'a>>, defs: &mut HashMap<Value, Value>) -> Result<(), String> {
+//Creates the top-level environment that scripts run in, with the standard library loaded
+pub fn new_environment() -> Environment {
     let libs = open_libs();
-    let mut ops = Vec::with_capacity(1024);
     let mut env = Environment::new();
     env.push_frame();
     for (key, rc) in libs.into_iter() {
         env.declare_var(key.to_string(), Value::Clip(ClipHolder::new(rc)));
     }
+    env
+}
 
+//Runs the statements, returning the value of the last statement if it's a bare expression
+pub fn evaluate<'a>(ast: &'a Vec<Stmt<'a>>, file: Rc<SourceFile>, env: &mut Environment,
+                    defs: &mut HashMap<Value, Value>) -> Result<Value, Error> {
+    let mut ops = OpList::new(file);
     match gen_stmt_list(&ast, &mut ops) {
         Ok(_) => (),
         Err(s) => {return Err(s)}
     };
+    let (ops, source_map) = ops.into_parts();
     if cfg!(debug) {
-        print_debug_info(&ops);
+        print_debug_info(&ops, &source_map);
     }
     let mut stack = Vec::with_capacity(256);
-    match execute(&mut ops, &mut stack, &mut env, defs) {
-        Ok(()) => Ok(()),
-        Err(s) => Err(s)
+    let depth = env.frame_depth();
+    if let Err(e) = execute(&ops, &source_map, &mut stack, env, defs) {
+        env.unwind_to(depth);
+        return Err(e);
+    }
+    match ast.last() {
+        Some(&Stmt{stmt: StmtType::Bare{..}, ..}) => Ok(stack.pop().unwrap_or(Value::Nil)),
+        _ => Ok(Value::Nil)
     }
 }
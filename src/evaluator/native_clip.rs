@@ -0,0 +1,142 @@
+use std::fmt::{Debug, Formatter};
+use std::fmt::Result as FmtResult;
+
+use super::value::Value;
+use super::clip::{Clip, ClipHolder};
+use super::environment::Environment;
+use super::convert::{FromValue, IntoValue};
+use error::Error;
+
+//Rust function that can be played like a clip. `Args` is only there to tell apart the impls
+//for functions taking the raw arg slice from ones taking typed args.
+pub trait NativeFn<Args>: 'static {
+    fn call_native(&self, name: &str, args: Vec<Value>, environment: &mut Environment)
+                   -> Result<Value, Error>;
+}
+
+//Marker for functions that take the arg slice as it is and do their own checking
+pub struct RawArgs;
+
+impl<F> NativeFn<RawArgs> for F where F: Fn(&[Value]) -> Result<Value, Error> + 'static {
+    fn call_native(&self, _: &str, args: Vec<Value>, _: &mut Environment) -> Result<Value, Error> {
+        self(&args[..])
+    }
+}
+
+//Marker for functions that also need the environment, like ones that play hooks
+pub struct EnvArgs;
+
+impl<F> NativeFn<EnvArgs> for F
+    where F: Fn(&[Value], &mut Environment) -> Result<Value, Error> + 'static {
+    fn call_native(&self, _: &str, args: Vec<Value>, environment: &mut Environment)
+                   -> Result<Value, Error> {
+        self(&args[..], environment)
+    }
+}
+
+//Return types of typed native functions; lets them either return a value or fail
+pub trait NativeResult {
+    fn into_result(self) -> Result<Value, Error>;
+}
+
+impl<T: IntoValue> NativeResult for T {
+    fn into_result(self) -> Result<Value, Error> {
+        Ok(self.into_value())
+    }
+}
+
+impl<T: IntoValue> NativeResult for Result<T, Error> {
+    fn into_result(self) -> Result<Value, Error> {
+        self.map(|v| v.into_value())
+    }
+}
+
+fn arity_error(name: &str, expected: usize, found: usize) -> Error {
+    Error::runtime(format!("Wrong number of args for `{}`: expected {} but got {}",
+                           name, expected, found))
+}
+
+macro_rules! native_fn_impl {
+    ($len:expr $(, $name:ident)*) => {
+        #[allow(non_snake_case, unused_mut, unused_variables)]
+        impl<F, R $(, $name)*> NativeFn<($($name,)*)> for F
+            where F: Fn($($name),*) -> R + 'static, R: NativeResult $(, $name: FromValue)* {
+            fn call_native(&self, name: &str, args: Vec<Value>, _: &mut Environment)
+                           -> Result<Value, Error> {
+                if args.len() != $len {
+                    return Err(arity_error(name, $len, args.len()));
+                }
+                let mut iter = args.into_iter().enumerate();
+                $(
+                    let (idx, arg) = iter.next().unwrap();
+                    let $name = match $name::from_value(arg) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            return Err(Error::runtime(format!("Bad arg {} for `{}`: {}",
+                                                              idx + 1, name, e.message())));
+                        }
+                    };
+                )*
+                self($($name),*).into_result()
+            }
+        }
+    }
+}
+
+native_fn_impl!(0);
+native_fn_impl!(1, A);
+native_fn_impl!(2, A, B);
+native_fn_impl!(3, A, B, C);
+native_fn_impl!(4, A, B, C, D);
+native_fn_impl!(5, A, B, C, D, E);
+
+pub struct NativeClip {
+    name: String,
+    func: Box<Fn(&str, Vec<Value>, &mut Environment) -> Result<Value, Error>>
+}
+
+impl NativeClip {
+    pub fn new<Args, F: NativeFn<Args>>(name: &str, func: F) -> NativeClip {
+        NativeClip {
+            name: name.to_string(),
+            func: Box::new(move |name: &str, args: Vec<Value>, environment: &mut Environment| {
+                func.call_native(name, args, environment)
+            })
+        }
+    }
+}
+
+//Native clips handed out as methods, like the ones on lists and files, take the clip they're on
+//as their first arg, since they're meant to be played with `|`
+pub fn native_value(clip: NativeClip) -> Value {
+    Value::Clip(ClipHolder::new(Box::new(clip)))
+}
+
+impl Debug for NativeClip {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "NativeClip({})", self.name)
+    }
+}
+
+#[allow(unused_variables)]
+impl Clip for NativeClip {
+    fn get(&self, key: &Value) -> Value {
+        Value::Nil
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime(format!("Cannot set a def on native clip `{}`", self.name)))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        false
+    }
+
+    fn play(&mut self, args: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
+        (self.func)(&self.name, args, environment)
+    }
+
+    fn signature(&self) -> Option<String> {
+        Some(format!("<native fn {}>", self.name))
+    }
+}
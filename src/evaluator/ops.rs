@@ -1,5 +1,6 @@
 use super::value::Value;
 use super::environment::ValueHolder;
+use super::source_map::SourceMap;
 use std::cell::RefCell;
 use std::rc::Rc;
 
@@ -10,6 +11,7 @@ pub enum Op {
     PushClip(ClipParts), // .. -> clip, ..
     MakeTuple(usize), // 1, ..., N, .. -> (1, ..., N), ..
     ExpandTuple(usize), // (1, ..., N), .. -> 1, ..., N, ..
+    MakeList(usize), // 1, ..., N, .. -> [1, ..., N], ..
     Jump(usize), // .. -> ..
     JumpIfFalse(usize), // bool, .. -> ..
     JumpTarget, // .. -> ..
@@ -17,6 +19,8 @@ pub enum Op {
     PushIterator, // a, .. -> ..
     PopIterator, // .. -> ..
     RetrieveIterator, // .. -> a, ..
+    PushHandler(usize), // .. -> ..
+    PopHandler, // .. -> ..
     //Scoping
     PushScope, // .. -> ..
     PopScope, // .. -> ..
@@ -30,6 +34,7 @@ pub enum Op {
     Def(Box<Value>), // clip, value, .. -> ..
     DefPop, // value, key, .. -> ..
     DefSelf(Box<Value>), // value, .. -> ..
+    StoreIndex, // key, clip, value, .. -> ..
     //Postfixes
     GetAndAccess, // b, a, .. -> a.b, ..
     Access(Box<Value>), // a, .. -> a.b, a, ..
@@ -37,6 +42,7 @@ pub enum Op {
     Play(usize), // 1, ..., N, a, .. -> a(1, ..., N), ..
     PlaySelf(usize), // 1, ..., N, func, self, .. -> func(self, 1, ..., N), ..
     //Unary ops
+    ToStr, // a, .. -> "{a}", ..
     Get, // a, .. -> $a ..
     Neg, // a, .. -> -a ..
     Not, // a, .. -> !a ..
@@ -46,6 +52,7 @@ pub enum Op {
     Mul, // b, a, .. -> a * b, ..
     Div, // b, a, .. -> a / b, ..
     Mod, // b, a, .. -> a % b, ..
+    Pow, // b, a, .. -> a ^ b, ..
     In, // b, a, .. -> a in b, ..
     Lt, // b, a, .. -> a < b, ..
     Lte, // b, a, .. -> a <= b, ..
@@ -61,5 +68,6 @@ pub enum Op {
 pub struct ClipParts {
     pub params: Vec<String>,
     pub returns: Vec<String>,
-    pub ops: Vec<Op>
+    pub ops: Vec<Op>,
+    pub source_map: SourceMap
 }
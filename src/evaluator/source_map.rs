@@ -0,0 +1,86 @@
+use std::fmt::{Debug, Display, Formatter};
+use std::fmt::Result as FmtResult;
+use std::rc::Rc;
+
+use error::Span;
+
+pub struct SourceFile {
+    pub name: String,
+    pub lines: Vec<String>
+}
+
+//Only show the name, otherwise dumping ops would print every clip's entire source file
+impl Debug for SourceFile {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "SourceFile({})", self.name)
+    }
+}
+
+impl SourceFile {
+    pub fn new(name: &str, source: &str) -> SourceFile {
+        SourceFile {
+            name: name.to_string(),
+            lines: source.lines().map(|l| l.to_string()).collect()
+        }
+    }
+
+    pub fn span(&self, line: usize, col: usize) -> Span {
+        let line_string = match self.lines.get(line) {
+            Some(l) => &l[..],
+            None => ""
+        };
+        Span::new(&self.name[..], line, col, line_string)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct SourcePos {
+    pub line: usize,
+    pub col: usize
+}
+
+//Location of a single op in the source file it was generated from
+#[derive(Debug, Clone)]
+pub struct SourceLocation {
+    pub file: Rc<SourceFile>,
+    pub pos: SourcePos
+}
+
+impl SourceLocation {
+    pub fn to_span(&self) -> Span {
+        self.file.span(self.pos.line, self.pos.col)
+    }
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{}:{}:{}", self.file.name, self.pos.line + 1, self.pos.col + 1)
+    }
+}
+
+//Positions of a list of ops, indexed the same way as the ops themselves
+#[derive(Debug, Clone)]
+pub struct SourceMap {
+    pub file: Rc<SourceFile>,
+    pub positions: Vec<SourcePos>
+}
+
+impl SourceMap {
+    pub fn new(file: Rc<SourceFile>, positions: Vec<SourcePos>) -> SourceMap {
+        SourceMap {
+            file: file,
+            positions: positions
+        }
+    }
+
+    pub fn location(&self, pc: usize) -> SourceLocation {
+        let pos = match self.positions.get(pc) {
+            Some(p) => *p,
+            None => SourcePos{line: 0, col: 0}
+        };
+        SourceLocation {
+            file: self.file.clone(),
+            pos: pos
+        }
+    }
+}
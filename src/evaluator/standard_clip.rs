@@ -2,28 +2,33 @@ use std::collections::HashMap;
 
 use super::value::Value;
 use super::environment::Environment;
-use super::clip::Clip;
+use super::clip::{Clip, ClipHolder};
 use super::vm::execute;
 use super::ops::Op;
+use super::source_map::SourceMap;
+use error::Error;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct StdClip {
     params: Vec<String>,
     returns: Vec<String>,
     ops: Vec<Op>,
+    source_map: SourceMap,
     defs: HashMap<Value, Value>
 }
 
 impl StdClip {
-    pub fn new(params: Vec<String>, returns: Vec<String>, ops: Vec<Op>) -> StdClip {
-        Self::new_with_defs(params, returns, ops, HashMap::new())
+    pub fn new(params: Vec<String>, returns: Vec<String>, ops: Vec<Op>, source_map: SourceMap) -> StdClip {
+        Self::new_with_defs(params, returns, ops, source_map, HashMap::new())
     }
 
-    pub fn new_with_defs(params: Vec<String>, returns: Vec<String>, ops: Vec<Op>, defs: HashMap<Value, Value>) -> StdClip {
+    pub fn new_with_defs(params: Vec<String>, returns: Vec<String>, ops: Vec<Op>,
+                         source_map: SourceMap, defs: HashMap<Value, Value>) -> StdClip {
         StdClip {
             params: params,
             returns: returns,
             ops: ops,
+            source_map: source_map,
             defs: defs
         }
     }
@@ -37,12 +42,45 @@ impl Clip for StdClip {
         }
     }
 
-    fn set(&mut self, key: Value, value: Value) -> Result<(), String> {
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
         self.defs.insert(key, value);
         Ok(())
     }
 
-    fn play(&mut self, params: Vec<Value>, environment: &mut Environment) -> Result<Value, String> {
+    fn contains(&self, key: &Value) -> bool {
+        self.defs.contains_key(key)
+    }
+
+    fn keys(&self) -> Vec<Value> {
+        self.defs.keys().cloned().collect()
+    }
+
+    //Vars captured by closures stay shared with the original, only the defs are copied
+    fn copy(&self) -> Option<Box<Clip>> {
+        Some(Box::new(self.clone()))
+    }
+
+    //Only clips with params or returns get shown as functions
+    fn signature(&self) -> Option<String> {
+        if self.params.is_empty() && self.returns.is_empty() {
+            return None;
+        }
+        let returns = match self.returns.len() {
+            0 => String::new(),
+            1 => format!(" -> {}", self.returns[0]),
+            _ => format!(" -> ({})", self.returns.join(", "))
+        };
+        Some(format!("fn({}){}", self.params.join(", "), returns))
+    }
+
+    fn proto(&self) -> Option<ClipHolder> {
+        match self.defs.get(&Value::String("proto".to_string())) {
+            Some(&Value::Clip(ref p)) => Some(p.clone()),
+            _ => None
+        }
+    }
+
+    fn play(&mut self, params: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
         for (ident, value) in self.params.iter().zip(params.into_iter()) {
             environment.declare_var(ident.clone(), value);
         }
@@ -50,7 +88,7 @@ impl Clip for StdClip {
             environment.declare_var(ident.clone(), Value::Int(-10));
         }
         let mut stack = Vec::new();
-        try!(execute(&self.ops, &mut stack, environment, &mut self.defs));
+        try!(execute(&self.ops, &self.source_map, &mut stack, environment, &mut self.defs));
         if self.returns.len() == 0 {
             Ok(Value::Nil)
         } else if self.returns.len() == 1 {
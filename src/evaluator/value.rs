@@ -1,13 +1,19 @@
+use std::cmp::Ordering;
 use std::fmt::{Debug, Display, Formatter, Error};
 use std::fmt::Result as FmtResult;
-use num::Float;
+use num::{Float, FromPrimitive, ToPrimitive};
+use num::bigint::BigInt;
 use std::mem;
+use std::f64;
 
 use super::clip::{ClipHolder};
+use super::hooks::repr_string;
 
 #[derive(Debug, Clone, Eq, PartialEq, Hash)]
 pub enum Value {
     Int(i64),
+    //Ints that don't fit in an i64. Smaller results always get turned back into `Int`s.
+    BigInt(BigInt),
     Float(FloatWrap),
     Bool(bool),
     String(String),
@@ -16,10 +22,103 @@ pub enum Value {
     Nil
 }
 
+//Closest float to a big int, going to infinity if it's too big
+pub fn big_to_f64(n: &BigInt) -> f64 {
+    match n.to_f64() {
+        Some(f) => f,
+        None => n.to_string().parse().unwrap_or(f64::NAN)
+    }
+}
+
+impl Value {
+    pub fn from_big(n: BigInt) -> Value {
+        match n.to_i64() {
+            Some(i) => Value::Int(i),
+            None => Value::BigInt(n)
+        }
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        match *self {
+            Value::Int(_) | Value::BigInt(_) => "int",
+            Value::Float(_) => "float",
+            Value::Bool(_) => "bool",
+            Value::String(_) => "string",
+            Value::Tuple(_) => "tuple",
+            Value::Clip(_) => "clip",
+            Value::Nil => "nil"
+        }
+    }
+
+    //Total order used wherever clip keys need to come out in a predictable order. Values are
+    //grouped by type, and ints and floats are compared with each other numerically.
+    pub fn key_order(&self, other: &Value) -> Ordering {
+        fn type_rank(v: &Value) -> u8 {
+            match *v {
+                Value::Nil => 0,
+                Value::Bool(_) => 1,
+                Value::Int(_) | Value::BigInt(_) | Value::Float(_) => 2,
+                Value::String(_) => 3,
+                Value::Tuple(_) => 4,
+                Value::Clip(_) => 5
+            }
+        }
+        fn cmp_floats(a: f64, b: f64) -> Ordering {
+            a.partial_cmp(&b).unwrap_or_else(|| a.is_nan().cmp(&b.is_nan()))
+        }
+        match (self, other) {
+            (&Value::Bool(a), &Value::Bool(b)) => a.cmp(&b),
+            (&Value::Int(a), &Value::Int(b)) => a.cmp(&b),
+            (&Value::Float(ref a), &Value::Float(ref b)) => cmp_floats(a.get(), b.get()),
+            //Equal ints and floats still need an order, so ints go first
+            (&Value::Int(a), &Value::Float(ref b)) => {
+                match cmp_floats(a as f64, b.get()) {
+                    Ordering::Equal => Ordering::Less,
+                    ord => ord
+                }
+            }
+            (&Value::Float(ref a), &Value::Int(b)) => {
+                match cmp_floats(a.get(), b as f64) {
+                    Ordering::Equal => Ordering::Greater,
+                    ord => ord
+                }
+            }
+            (&Value::BigInt(ref a), &Value::BigInt(ref b)) => a.cmp(b),
+            (&Value::Int(a), &Value::BigInt(ref b)) => BigInt::from_i64(a).unwrap().cmp(b),
+            (&Value::BigInt(ref a), &Value::Int(b)) => a.cmp(&BigInt::from_i64(b).unwrap()),
+            (&Value::BigInt(ref a), &Value::Float(ref b)) => {
+                match cmp_floats(big_to_f64(a), b.get()) {
+                    Ordering::Equal => Ordering::Less,
+                    ord => ord
+                }
+            }
+            (&Value::Float(ref a), &Value::BigInt(ref b)) => {
+                match cmp_floats(a.get(), big_to_f64(b)) {
+                    Ordering::Equal => Ordering::Greater,
+                    ord => ord
+                }
+            }
+            (&Value::String(ref a), &Value::String(ref b)) => a.cmp(b),
+            (&Value::Tuple(ref a), &Value::Tuple(ref b)) => {
+                for (x, y) in a.iter().zip(b.iter()) {
+                    let ord = x.key_order(y);
+                    if ord != Ordering::Equal {
+                        return ord;
+                    }
+                }
+                a.len().cmp(&b.len())
+            }
+            (&Value::Clip(ref a), &Value::Clip(ref b)) => a.id().cmp(&b.id()),
+            (a, b) => type_rank(a).cmp(&type_rank(b))
+        }
+    }
+}
+
 impl Display for Value {
     fn fmt<'r>(&'r self, formatter: &mut Formatter) -> FmtResult {
         match self {
             &Value::Int(i) => write!(formatter, "{}", i),
+            &Value::BigInt(ref n) => write!(formatter, "{}", n),
             &Value::Float(ref f) => write!(formatter, "{}", f),
             &Value::Bool(b) => write!(formatter, "{}", b),
             &Value::String(ref s) => write!(formatter, "{}", s),
@@ -46,7 +145,7 @@ impl Display for Value {
                     Err(e) => Err(e)
                 }
             }
-            &Value::Clip(_) => write!(formatter, "<Clip>"),
+            &Value::Clip(_) => write!(formatter, "{}", repr_string(self)),
             &Value::Nil => write!(formatter, "nil"),
         }
     }
@@ -2,10 +2,19 @@ use std::collections::{HashMap, HashSet};
 use std::fmt::Display;
 
 use super::ops::Op;
-use super::value::{Value, FloatWrap};
+use super::value::{Value, FloatWrap, big_to_f64};
 use super::environment::{Environment, RefType};
 use super::standard_clip::StdClip;
-use super::clip::ClipHolder;
+use super::clip::{ClipHolder, check_proto};
+use super::list_clip::ListClip;
+use super::iter_clip::iterator_for;
+use super::hooks::{get_hook, callee, display_string};
+use super::error_value::error_value;
+use super::source_map::SourceMap;
+use libhc::string;
+use error::Error;
+use num::{Zero, FromPrimitive, pow};
+use num::bigint::BigInt;
 
 macro_rules! check_bin_op(
     ($a:expr, $b:expr, $op_name:expr, $stack:expr, $pc:expr, [ $($lhs_type:path, $rhs_type:path => $f:expr => $result_type:path),+ ]) => ({
@@ -48,21 +57,276 @@ macro_rules! format_try {
     ($pc:expr, $expr:expr) => (match $expr {
         Result::Ok(val) => val,
         Result::Err(err) => {
-            return exec_failure($pc, err);
+            return Err(($pc, Error::from(err)));
         }
     })
 }
 
-fn exec_failure<T, D: Display>(pc: usize, message: D) -> Result<T, String> {
-    Err(format!("EXECUTION FAILURE at PC {}: {}", pc, message))
+//Failures keep track of the pc they happened at so that they can be mapped back to the source
+fn exec_failure<T, D: Display>(pc: usize, message: D) -> Result<T, (usize, Error)> {
+    Err((pc, Error::runtime(message)))
 }
 
-pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
-                   vars: &mut Environment,
-                   defs: &mut HashMap<Value, Value>) -> Result<(), String> {
-    let mut pc: usize = 0;
-    let len = ops.len();
+fn int_pow(base: i64, exp: i64) -> Result<Value, String> {
+    //Negative exponents can't be represented as an int, so fall back to a float
+    if exp < 0 {
+        return Ok(Value::Float(FloatWrap::new((base as f64).powf(exp as f64))));
+    }
+    let mut result: i64 = 1;
+    let mut cur_base = base;
+    let mut cur_exp = exp;
+    while cur_exp > 0 {
+        if cur_exp & 1 == 1 {
+            result = match result.checked_mul(cur_base) {
+                Some(r) => r,
+                None => {return big_pow(big(base), exp);}
+            };
+        }
+        cur_exp >>= 1;
+        if cur_exp > 0 {
+            cur_base = match cur_base.checked_mul(cur_base) {
+                Some(b) => b,
+                None => {return big_pow(big(base), exp);}
+            };
+        }
+    }
+    Ok(Value::Int(result))
+}
+
+//Most bits a power of a big int can have, so that a typo can't eat all of the memory
+const MAX_POW_BITS: u64 = 1 << 26;
+
+fn big_pow(base: BigInt, exp: i64) -> Result<Value, String> {
+    if exp < 0 {
+        return Ok(Value::Float(FloatWrap::new(big_to_f64(&base).powf(exp as f64))));
+    }
+    //The result has about as many bits as the base has times the exponent
+    if (base.bits() as u64).saturating_mul(exp as u64) > MAX_POW_BITS {
+        return Err(format!("raising a {}-bit int to the power of {} gives too big a result",
+                           base.bits(), exp));
+    }
+    Ok(Value::from_big(pow(base, exp as usize)))
+}
+
+//Ints mixed with floats get turned into floats, and ints mixed with big ints into big ints,
+//including inside of tuples that are the same length
+fn promote(a: Value, b: Value) -> (Value, Value) {
+    match (a, b) {
+        (Value::Int(x), Value::Float(y)) => (Value::Float(FloatWrap::new(x as f64)), Value::Float(y)),
+        (Value::Float(x), Value::Int(y)) => (Value::Float(x), Value::Float(FloatWrap::new(y as f64))),
+        (Value::Int(x), Value::BigInt(y)) => (Value::BigInt(big(x)), Value::BigInt(y)),
+        (Value::BigInt(x), Value::Int(y)) => (Value::BigInt(x), Value::BigInt(big(y))),
+        (Value::BigInt(x), Value::Float(y)) => (Value::Float(FloatWrap::new(big_to_f64(&x))), Value::Float(y)),
+        (Value::Float(x), Value::BigInt(y)) => (Value::Float(x), Value::Float(FloatWrap::new(big_to_f64(&y)))),
+        (Value::Tuple(lhs_vals), Value::Tuple(rhs_vals)) if lhs_vals.len() == rhs_vals.len() => {
+            let pairs = lhs_vals.into_iter().zip(rhs_vals.into_iter());
+            let (lhs, rhs) = pairs.map(|(l, r)| promote(l, r)).unzip();
+            (Value::Tuple(lhs), Value::Tuple(rhs))
+        }
+        pair => pair
+    }
+}
+
+fn big(i: i64) -> BigInt {
+    BigInt::from_i64(i).unwrap()
+}
+
+//Arithmetic on numbers, and on tuples of them element by element. Ints that overflow get redone
+//as big ints, and dividing ints by zero fails instead of panicking.
+fn arith_values(op_name: &str, a: Value, b: Value, int_op: &Fn(i64, i64) -> Option<i64>,
+                big_op: &Fn(BigInt, BigInt) -> BigInt,
+                float_op: &Fn(f64, f64) -> f64) -> Result<Value, String> {
+    let divides = op_name == "/" || op_name == "%";
+    match promote(a, b) {
+        (Value::Int(x), Value::Int(y)) => {
+            if divides && y == 0 {
+                return Err(format!("can't perform operation {} with a divisor of 0", op_name));
+            }
+            match int_op(x, y) {
+                Some(result) => Ok(Value::Int(result)),
+                None => Ok(Value::from_big(big_op(big(x), big(y))))
+            }
+        }
+        (Value::BigInt(x), Value::BigInt(y)) => {
+            if divides && y.is_zero() {
+                return Err(format!("can't perform operation {} with a divisor of 0", op_name));
+            }
+            Ok(Value::from_big(big_op(x, y)))
+        }
+        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(FloatWrap::new(float_op(x.get(), y.get())))),
+        (Value::String(x), Value::String(y)) if op_name == "+" => Ok(Value::String(x + &y[..])),
+        (Value::Tuple(lhs_vals), Value::Tuple(rhs_vals)) => {
+            let mut result_vec = Vec::new();
+            for (lhs, rhs) in lhs_vals.into_iter().zip(rhs_vals.into_iter()) {
+                result_vec.push(try!(arith_values(op_name, lhs, rhs, int_op, big_op, float_op)));
+            }
+            Ok(Value::Tuple(result_vec))
+        }
+        (Value::Tuple(_), _) => Err(format!("can't perform operation {} with a tuple and a non-tuple", op_name)),
+        (a, b) => Err(operand_error(op_name, a, b))
+    }
+}
+
+//Numbers on the LHS are shown bare, like the errors from `check_bin_op!`
+fn operand_error(op_name: &str, a: Value, b: Value) -> String {
+    match a {
+        Value::Int(_) | Value::BigInt(_) | Value::Float(_) => {
+            format!("can't perform operation {} with LHS of {} and RHS of {:?}", op_name, a, b)
+        }
+        Value::String(ref s) if op_name == "+" => {
+            format!("can't perform operation {} with LHS of {:?} and RHS of {:?}", op_name, s, b)
+        }
+        a => format!("can't perform operation {} with LHS of {:?}", op_name, a)
+    }
+}
+
+fn pow_values(a: Value, b: Value) -> Result<Value, String> {
+    match (a, b) {
+        (Value::Int(x), Value::Int(y)) => int_pow(x, y),
+        (Value::BigInt(x), Value::Int(y)) => big_pow(x, y),
+        (Value::Int(_), Value::BigInt(y)) | (Value::BigInt(_), Value::BigInt(y)) => {
+            Err(format!("exponent {} is too big", y))
+        }
+        (Value::Float(x), Value::Float(y)) => Ok(Value::Float(FloatWrap::new(x.get().powf(y.get())))),
+        (Value::Int(x), Value::Float(y)) => Ok(Value::Float(FloatWrap::new((x as f64).powf(y.get())))),
+        (Value::Float(x), Value::Int(y)) => Ok(Value::Float(FloatWrap::new(x.get().powf(y as f64)))),
+        (Value::BigInt(x), Value::Float(y)) => Ok(Value::Float(FloatWrap::new(big_to_f64(&x).powf(y.get())))),
+        (Value::Float(x), Value::BigInt(y)) => Ok(Value::Float(FloatWrap::new(x.get().powf(big_to_f64(&y))))),
+        (Value::Tuple(lhs_vals), Value::Tuple(rhs_vals)) => {
+            let mut result_vec = Vec::new();
+            for (lhs, rhs) in lhs_vals.into_iter().zip(rhs_vals.into_iter()) {
+                result_vec.push(try!(pow_values(lhs, rhs)));
+            }
+            Ok(Value::Tuple(result_vec))
+        }
+        (Value::Tuple(_), _) => Err("can't perform operation ^ with a tuple and a non-tuple".to_string()),
+        (a, b) => Err(operand_error("^", a, b))
+    }
+}
+
+fn play_clip(clip: &mut ClipHolder, args: Vec<Value>, vars: &mut Environment, source_map: &SourceMap,
+             pc: usize) -> Result<Value, (usize, Error)> {
+    Ok(format_try!(pc, vars.play(clip, args, Some(source_map.location(pc)))))
+}
+
+//Defs made while a clip is playing go straight to it, so a `proto` needs the same check as `set`
+fn check_def(key: &Value, value: &Value, vars: &Environment) -> Result<(), Error> {
+    match (key, value, vars.playing_clip()) {
+        (&Value::String(ref k), &Value::Clip(ref proto), Some(id)) if k == "proto" => check_proto(id, proto),
+        _ => Ok(())
+    }
+}
+
+//Clips with a `__call` hook have it played instead, with the clip as the first arg
+fn play_value(value: Value, args: Vec<Value>, vars: &mut Environment, source_map: &SourceMap,
+              pc: usize) -> Result<Value, (usize, Error)> {
+    match callee(value, args) {
+        Some((mut c, args)) => play_clip(&mut c, args, vars, source_map, pc),
+        None => exec_failure(pc, "can't run a non-clip")
+    }
+}
+
+//Plays the hook for an operator if either operand is a clip that has one, with the operands in order
+fn bin_op_hook(name: &str, a: &Value, b: &Value, vars: &mut Environment, source_map: &SourceMap,
+               pc: usize) -> Result<Option<Value>, (usize, Error)> {
+    let hook = match get_hook(a, name) {
+        Some(h) => Some(h),
+        None => get_hook(b, name)
+    };
+    match hook {
+        Some(mut h) => {
+            let result = try!(play_clip(&mut h, vec![a.clone(), b.clone()], vars, source_map, pc));
+            Ok(Some(result))
+        }
+        None => Ok(None)
+    }
+}
+
+//Values are equal if an `__eq` hook says so, otherwise clips are only equal to themselves
+//The hook only gets played when both sides are clips, so comparing with nil never reaches it
+fn values_equal(a: Value, b: Value, vars: &mut Environment, source_map: &SourceMap,
+                pc: usize) -> Result<bool, (usize, Error)> {
+    let hook_result = match (&a, &b) {
+        (&Value::Clip(_), &Value::Clip(_)) => try!(bin_op_hook("__eq", &a, &b, vars, source_map, pc)),
+        _ => None
+    };
+    match hook_result {
+        Some(Value::Bool(equal)) => Ok(equal),
+        Some(v) => exec_failure(pc, format!("`__eq` must return a bool, not {}", v.type_name())),
+        None => {
+            match promote(a, b) {
+                //Floats are compared by value here, unlike as keys, so nan isn't equal to itself
+                (Value::Float(x), Value::Float(y)) => Ok(x.get() == y.get()),
+                (a, b) => Ok(a == b)
+            }
+        }
+    }
+}
+
+//Fills in where an error happened, if it came from a native clip that didn't know
+fn locate_error(err: Error, pc: usize, source_map: &SourceMap, vars: &Environment) -> Error {
+    match err {
+        //Errors that come from deeper plays already know where they happened
+        Error::Runtime{span: None, message, value, ..} => {
+            Error::Runtime {
+                span: Some(source_map.location(pc).to_span()),
+                message: message,
+                value: value,
+                trace: vars.call_trace()
+            }
+        }
+        Error::Io{span: None, message} => {
+            Error::Io {
+                span: Some(source_map.location(pc).to_span()),
+                message: message
+            }
+        }
+        err => err
+    }
+}
+
+pub fn execute(ops: &Vec<Op>, source_map: &SourceMap, stack: &mut Vec<Value>,
+               vars: &mut Environment,
+               defs: &mut HashMap<Value, Value>) -> Result<(), Error> {
     let mut iterators = Vec::new();
+    let mut handlers: Vec<Handler> = Vec::new();
+    let mut start = 0;
+    loop {
+        match execute_ops(ops, source_map, stack, vars, defs, start, &mut iterators, &mut handlers) {
+            Ok(()) => {return Ok(());}
+            Err((pc, err)) => {
+                let err = locate_error(err, pc, source_map, vars);
+                match handlers.pop() {
+                    //Put everything back the way it was when the `try` block began
+                    Some(handler) => {
+                        vars.restore(handler.frame_depth, handler.call_depth);
+                        iterators.truncate(handler.iterators);
+                        stack.truncate(handler.stack_len);
+                        stack.push(error_value(err));
+                        start = handler.target;
+                    }
+                    None => {return Err(err);}
+                }
+            }
+        }
+    }
+}
+
+//State to restore when a `try` block catches an error
+struct Handler {
+    target: usize,
+    frame_depth: usize,
+    call_depth: usize,
+    iterators: usize,
+    stack_len: usize
+}
+
+fn execute_ops(ops: &Vec<Op>, source_map: &SourceMap, stack: &mut Vec<Value>,
+               vars: &mut Environment,
+               defs: &mut HashMap<Value, Value>, start: usize, iterators: &mut Vec<Value>,
+               handlers: &mut Vec<Handler>) -> Result<(), (usize, Error)> {
+    let mut pc: usize = start;
+    let len = ops.len();
     while pc < len {
         match ops[pc] {
             Op::Push(ref v) => {stack.push((**v).clone());},
@@ -126,7 +390,8 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
                 stack.push(Value::Clip(ClipHolder::new(Box::new(StdClip::new(
                     clip.params.clone(),
                     clip.returns.clone(),
-                    new_ops
+                    new_ops,
+                    clip.source_map.clone()
                 )))));
             }
             Op::MakeTuple(arity) => {
@@ -136,6 +401,13 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
                 }
                 stack.push(Value::Tuple(tuple_vec));
             }
+            Op::MakeList(len) => {
+                let mut items = Vec::with_capacity(len);
+                for _ in 0..len {
+                    items.push(stack.pop().unwrap());
+                }
+                stack.push(Value::Clip(ClipHolder::new(Box::new(ListClip::new(items)))));
+            }
             Op::ExpandTuple(arity) => {
                 if let Value::Tuple(tup_vec) = stack.pop().unwrap() {
                     if arity != tup_vec.len() {
@@ -164,7 +436,8 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
             Op::Return => {return Ok(());},
             Op::PushIterator => {
                 let a = stack.pop().unwrap();
-                iterators.push(a);
+                let iterator = format_try!(pc, iterator_for(a, vars));
+                iterators.push(iterator);
             }
             Op::PopIterator => {
                 iterators.pop();
@@ -173,6 +446,18 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
                 let idx = iterators.len() - 1;
                 stack.push(iterators[idx].clone());
             }
+            Op::PushHandler(target) => {
+                handlers.push(Handler {
+                    target: target,
+                    frame_depth: vars.frame_depth(),
+                    call_depth: vars.call_depth(),
+                    iterators: iterators.len(),
+                    stack_len: stack.len()
+                });
+            }
+            Op::PopHandler => {
+                handlers.pop();
+            }
             Op::PushScope => vars.push_frame(),
             Op::PopScope => vars.pop_frame(),
             Op::Load(ref s) => {
@@ -206,10 +491,9 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
             }
             Op::Def(ref key) => {
                 match stack.pop().unwrap() {
-                    Value::Clip(ref mut c) => {
-                        let mut clip = c.borrow_mut();
+                    Value::Clip(c) => {
                         let value = stack.pop().unwrap();
-                        format_try!(pc, clip.set((**key).clone(), value));
+                        format_try!(pc, c.set((**key).clone(), value));
                     }
                     _ => {return exec_failure(pc, "can't def on a non-clip");}
                 };
@@ -217,41 +501,52 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
             Op::DefPop => {
                 let key = stack.pop().unwrap();
                 let value = stack.pop().unwrap();
+                format_try!(pc, check_def(&key, &value, vars));
                 defs.insert(key, value);
             }
             Op::DefSelf(ref key) => {
                 let value = stack.pop().unwrap();
+                format_try!(pc, check_def(key, &value, vars));
                 defs.insert((**key).clone(), value);
             }
-            Op::GetAndAccess => {
-                let b = stack.pop().unwrap();
+            Op::StoreIndex => {
+                let key = stack.pop().unwrap();
                 match stack.pop().unwrap() {
-                    Value::Clip(ref mut c) => {
-                        let clip = c.borrow_mut();
-                        let new_val = clip.get(&b);
-                        stack.push(new_val.clone());
+                    Value::Clip(c) => {
+                        let value = stack.pop().unwrap();
+                        format_try!(pc, c.set(key, value));
                     }
+                    _ => {return exec_failure(pc, "can't index into a non-clip");}
+                };
+            }
+            Op::GetAndAccess => {
+                let b = stack.pop().unwrap();
+                let a = stack.pop().unwrap();
+                if let Some(mut hook) = get_hook(&a, "__index") {
+                    let result = try!(play_clip(&mut hook, vec![a, b], vars, source_map, pc));
+                    stack.push(result);
+                    pc += 1;
+                    continue;
+                }
+                match a {
+                    Value::Clip(c) => stack.push(c.get(&b)),
+                    Value::String(s) => stack.push(string::get(&s[..], &b)),
                     _ => {return exec_failure(pc, "can't access a non-clip");}
                 };
             }
             Op::Access(ref b) => {
                 let idx = stack.len() - 1;
                 let new_val = match stack[idx] {
-                    Value::Clip(ref mut c) => {
-                        let clip = c.borrow_mut();
-                        clip.get(b)
-                    }
+                    Value::Clip(ref c) => c.get(b),
+                    Value::String(ref s) => string::get(&s[..], b),
                     _ => {return exec_failure(pc, "can't access a non-clip");}
                 };
                 stack.push(new_val);
             }
             Op::AccessPop(ref b) => {
                 match stack.pop().unwrap() {
-                    Value::Clip(ref mut c) => {
-                        let clip = c.borrow_mut();
-                        let new_val = clip.get(b);
-                        stack.push(new_val.clone());
-                    }
+                    Value::Clip(c) => stack.push(c.get(b)),
+                    Value::String(s) => stack.push(string::get(&s[..], b)),
                     _ => {return exec_failure(pc, "can't access a non-clip");}
                 };
             }
@@ -260,57 +555,55 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
                 for _ in 0..n {
                     params.push(stack.pop().unwrap());
                 }
-                match stack.pop().unwrap() {
-                    Value::Clip(ref mut c) => {
-                        let mut clip = c.borrow_mut();
-                        vars.push_frame();
-                        let result = format_try!(pc, clip.play(params, vars));
-                        vars.pop_frame();
-                        stack.push(result);
-                    }
-                    _ => {return exec_failure(pc, "can't run a non-clip");}
-                }
+                let clip = stack.pop().unwrap();
+                let result = try!(play_value(clip, params, vars, source_map, pc));
+                stack.push(result);
             }
             Op::PlaySelf(n) => {
                 let mut params = Vec::new();
                 for _ in 0..n {
                     params.push(stack.pop().unwrap());
                 }
-                match stack.pop().unwrap() {
-                    Value::Clip(ref mut c) => {
-                        let mut clip = c.borrow_mut();
-                        params.insert(0, stack.pop().unwrap());
-                        vars.push_frame();
-                        let result = format_try!(pc, clip.play(params, vars));
-                        vars.pop_frame();
-                        stack.push(result);
-                    }
-                    _ => {return exec_failure(pc, "can't run a non-clip");}
-                }
+                let clip = stack.pop().unwrap();
+                params.insert(0, stack.pop().unwrap());
+                let result = try!(play_value(clip, params, vars, source_map, pc));
+                stack.push(result);
             }
             //Unary ops
             Op::Get => {
                 match stack.pop().unwrap() {
-                    Value::Clip(ref mut c) => {
-                        {
-                            let mut clip = c.borrow_mut();
-                            vars.push_frame();
-                            format_try!(pc, clip.play(Vec::new(), vars));
-                            vars.pop_frame();
-                        }
-                        stack.push(Value::Clip(c.clone()));
+                    Value::Clip(mut c) => {
+                        try!(play_clip(&mut c, Vec::new(), vars, source_map, pc));
+                        stack.push(Value::Clip(c));
                     }
                     _ => {return exec_failure(pc, "can't use the get operator on a non-clip");}
                 }
             }
             Op::Neg => {
                 let a = stack.pop().unwrap();
+                if let Some(mut hook) = get_hook(&a, "__neg") {
+                    let result = try!(play_clip(&mut hook, vec![a], vars, source_map, pc));
+                    stack.push(result);
+                    pc += 1;
+                    continue;
+                }
                 match a {
-                    Value::Int(i) => stack.push(Value::Int(-i)),
+                    Value::Int(i) => {
+                        match i.checked_neg() {
+                            Some(result) => stack.push(Value::Int(result)),
+                            None => stack.push(Value::from_big(-big(i)))
+                        }
+                    }
+                    Value::BigInt(n) => stack.push(Value::from_big(-n)),
                     Value::Float(f) => stack.push(Value::Float(FloatWrap::new(-f.get()))),
                     _ => {return exec_failure(pc, "cannot negate a non-numeric value");}
                 }
             }
+            Op::ToStr => {
+                let a = stack.pop().unwrap();
+                let s = format_try!(pc, display_string(&a, vars));
+                stack.push(Value::String(s));
+            }
             Op::Not => {
                 let a = stack.pop().unwrap();
                 match a {
@@ -322,93 +615,152 @@ pub fn execute(ops: &Vec<Op>, stack: &mut Vec<Value>,
             Op::Add => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "+", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x + y} => Value::Int,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {FloatWrap::new(x.get() + y.get())} => Value::Float,
-                    Value::String, Value::String => |x: String, y: String| {x.clone() + &y[..]} => Value::String
-                ])
+                if let Some(result) = try!(bin_op_hook("__add", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let result = format_try!(pc, arith_values("+", a, b, &i64::checked_add, &|x, y| x + y,
+                                                              &|x, y| x + y));
+                    stack.push(result);
+                }
             }
             Op::Sub => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "-", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x - y} => Value::Int,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {FloatWrap::new(x.get() - y.get())} => Value::Float
-                ])
+                if let Some(result) = try!(bin_op_hook("__sub", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let result = format_try!(pc, arith_values("-", a, b, &i64::checked_sub, &|x, y| x - y,
+                                                              &|x, y| x - y));
+                    stack.push(result);
+                }
             }
             Op::Mul => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "*", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x * y} => Value::Int,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {FloatWrap::new(x.get() * y.get())} => Value::Float
-                ])
+                if let Some(result) = try!(bin_op_hook("__mul", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let result = format_try!(pc, arith_values("*", a, b, &i64::checked_mul, &|x, y| x * y,
+                                                              &|x, y| x * y));
+                    stack.push(result);
+                }
             }
             Op::Div => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "/", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x / y} => Value::Int,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {FloatWrap::new(x.get() / y.get())} => Value::Float
-                ])
+                if let Some(result) = try!(bin_op_hook("__div", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let result = format_try!(pc, arith_values("/", a, b, &i64::checked_div, &|x, y| x / y,
+                                                              &|x, y| x / y));
+                    stack.push(result);
+                }
             }
             Op::Mod => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "%", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x % y} => Value::Int,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {FloatWrap::new(x.get() % y.get())} => Value::Float
-                ])
+                if let Some(result) = try!(bin_op_hook("__mod", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let result = format_try!(pc, arith_values("%", a, b, &i64::checked_rem, &|x, y| x % y,
+                                                              &|x, y| x % y));
+                    stack.push(result);
+                }
+            }
+            Op::Pow => {
+                let b = stack.pop().unwrap();
+                let a = stack.pop().unwrap();
+                if let Some(result) = try!(bin_op_hook("__pow", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let result = format_try!(pc, pow_values(a, b));
+                    stack.push(result);
+                }
             }
             Op::In => {
-                // let b = stack.pop().unwrap();
-                // let a = stack.pop().unwrap();
-                // match b {
-
-                // }
-                panic!("not implemented: binary in")
+                let b = stack.pop().unwrap();
+                let a = stack.pop().unwrap();
+                match b {
+                    Value::Clip(c) => stack.push(Value::Bool(c.contains(&a))),
+                    Value::Tuple(ref vals) => {
+                        stack.push(Value::Bool(vals.contains(&a)));
+                    }
+                    Value::String(ref s) => {
+                        match a {
+                            Value::String(ref sub) => stack.push(Value::Bool(s.contains(&sub[..]))),
+                            v => {return exec_failure(pc, format!("can't perform operation in with LHS of {:?} and a string RHS", v));}
+                        }
+                    }
+                    v => {return exec_failure(pc, format!("can't perform operation in with RHS of {:?}", v));}
+                }
             }
             Op::Lt => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "<", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x < y} => Value::Bool,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() < y.get()} => Value::Bool
-                ])
+                if let Some(result) = try!(bin_op_hook("__lt", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let (a, b) = promote(a, b);
+                    check_bin_op!(a, b, "<", stack, pc, [
+                        Value::Int, Value::Int => |x, y| {x < y} => Value::Bool,
+                        Value::BigInt, Value::BigInt => |x: BigInt, y: BigInt| {x < y} => Value::Bool,
+                        Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() < y.get()} => Value::Bool
+                    ])
+                }
             }
             Op::Lte => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, "<=", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x <= y} => Value::Bool,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() <= y.get()} => Value::Bool
-                ])
+                if let Some(result) = try!(bin_op_hook("__lte", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let (a, b) = promote(a, b);
+                    check_bin_op!(a, b, "<=", stack, pc, [
+                        Value::Int, Value::Int => |x, y| {x <= y} => Value::Bool,
+                        Value::BigInt, Value::BigInt => |x: BigInt, y: BigInt| {x <= y} => Value::Bool,
+                        Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() <= y.get()} => Value::Bool
+                    ])
+                }
             }
             Op::Gt => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, ">", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x > y} => Value::Bool,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() > y.get()} => Value::Bool
-                ])
+                if let Some(result) = try!(bin_op_hook("__gt", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let (a, b) = promote(a, b);
+                    check_bin_op!(a, b, ">", stack, pc, [
+                        Value::Int, Value::Int => |x, y| {x > y} => Value::Bool,
+                        Value::BigInt, Value::BigInt => |x: BigInt, y: BigInt| {x > y} => Value::Bool,
+                        Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() > y.get()} => Value::Bool
+                    ])
+                }
             }
             Op::Gte => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                check_bin_op!(a, b, ">=", stack, pc, [
-                    Value::Int, Value::Int => |x, y| {x >= y} => Value::Bool,
-                    Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() >= y.get()} => Value::Bool
-                ])
+                if let Some(result) = try!(bin_op_hook("__gte", &a, &b, vars, source_map, pc)) {
+                    stack.push(result);
+                } else {
+                    let (a, b) = promote(a, b);
+                    check_bin_op!(a, b, ">=", stack, pc, [
+                        Value::Int, Value::Int => |x, y| {x >= y} => Value::Bool,
+                        Value::BigInt, Value::BigInt => |x: BigInt, y: BigInt| {x >= y} => Value::Bool,
+                        Value::Float, Value::Float => |x: FloatWrap, y: FloatWrap| {x.get() >= y.get()} => Value::Bool
+                    ])
+                }
             }
             Op::Eq => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                stack.push(Value::Bool(a == b));
+                let equal = try!(values_equal(a, b, vars, source_map, pc));
+                stack.push(Value::Bool(equal));
             }
             Op::Neq => {
                 let b = stack.pop().unwrap();
                 let a = stack.pop().unwrap();
-                stack.push(Value::Bool(a != b));
+                let equal = try!(values_equal(a, b, vars, source_map, pc));
+                stack.push(Value::Bool(!equal));
             }
             Op::And => {
                 let b = stack.pop().unwrap();
@@ -1,60 +1,588 @@
 use std::io::prelude::*;
 use std::fs::File;
 use std::path::Path;
-use std::fmt::Display;
 use std::collections::HashMap;
+use std::rc::Rc;
 use lexer::Lexer;
 use evaluator::value::Value;
-use evaluator::evaluate;
-use ast::{Stmt};
+use evaluator::{evaluate, new_environment};
+use evaluator::environment::Environment;
+use evaluator::source_map::{SourceFile, SourceLocation, SourcePos};
+use evaluator::clip::ClipHolder;
+use evaluator::hooks::callee;
+use evaluator::native_clip::{NativeClip, NativeFn};
 use parser;
+use error::Error;
 
-fn interpreter_failure<T, D: Display>(message: D) -> Result<T, String> {
-    Err(format!("INTERPRETER FAILURE: {}", message))
-}
-
-pub struct Interpreter<'a> {
-    lexer: Lexer<'a>,
-    statements: Vec<Stmt<'a>>,
+pub struct Interpreter {
+    env: Environment,
     defs: HashMap<Value, Value>
 }
 
-impl<'a> Interpreter<'a> {
-    pub fn new() -> Interpreter<'a> {
+impl Interpreter {
+    pub fn new() -> Interpreter {
         Interpreter {
-            lexer: Lexer::new(),
-            statements: Vec::with_capacity(100),
+            env: new_environment(),
             defs: HashMap::with_capacity(100)
         }
     }
 
-    pub fn interpret_file(&'a mut self, filename: &str) -> Result<&mut HashMap<Value, Value>, String> {
+    //Makes a Rust function playable from scripts as a global clip. It can either take the raw
+    //`&[Value]` args, or typed args that get converted with `FromValue`, in which case wrong arg
+    //counts and types are reported as runtime errors before the function is called.
+    pub fn register_fn<Args, F: NativeFn<Args>>(&mut self, name: &str, func: F) {
+        let clip = NativeClip::new(name, func);
+        self.env.declare_var(name.to_string(), Value::Clip(ClipHolder::new(Box::new(clip))));
+    }
+
+    pub fn get_var(&mut self, name: &str) -> Option<Value> {
+        self.env.get_var(&name.to_string())
+    }
+
+    //Assigns to a global var, declaring it first if it doesn't exist yet
+    pub fn set_var(&mut self, name: &str, value: Value) -> Result<(), Error> {
+        match self.env.get_var(&name.to_string()) {
+            Some(_) => self.env.set_var(name.to_string(), value).map_err(Error::runtime),
+            None => {
+                self.env.declare_var(name.to_string(), value);
+                Ok(())
+            }
+        }
+    }
+
+    pub fn get_def(&self, key: &Value) -> Value {
+        match self.defs.get(key) {
+            Some(v) => v.clone(),
+            None => Value::Nil
+        }
+    }
+
+    pub fn set_def(&mut self, key: Value, value: Value) {
+        self.defs.insert(key, value);
+    }
+
+    //Plays a clip with the given args, the same way a script playing it would. Traces of errors
+    //from the play end at `<host>`, since it wasn't played from any source.
+    pub fn play(&mut self, clip: &Value, args: Vec<Value>) -> Result<Value, Error> {
+        let (mut holder, args) = match callee(clip.clone(), args) {
+            Some(c) => c,
+            None => {return Err(Error::runtime(format!("can't play a {}", clip.type_name())));}
+        };
+        let location = SourceLocation {
+            file: Rc::new(SourceFile::new("<host>", "")),
+            pos: SourcePos{line: 0, col: 0}
+        };
+        self.env.play(&mut holder, args, Some(location))
+    }
+
+    pub fn play_var(&mut self, name: &str, args: Vec<Value>) -> Result<Value, Error> {
+        match self.get_var(name) {
+            Some(clip) => self.play(&clip, args),
+            None => {
+                Err(Error::runtime(format!("Expected to find ident `{}`, but it wasn't found in any scope",
+                                           name)))
+            }
+        }
+    }
+
+    //Evaluates a chunk of source, returning the value of its last statement if it's a bare
+    //expression. Vars and defs stick around for any chunks evaluated afterwards.
+    pub fn eval_str(&mut self, source: &str, chunk_name: &str) -> Result<Value, Error> {
+        let source_file = Rc::new(SourceFile::new(chunk_name, source));
+        //Tokens and statements borrow from the source, but the generated ops don't,
+        //so nothing here needs to outlive the call
+        let mut lexer = Lexer::new();
+        let toks = match lexer.lex(source.to_string()) {
+            Err(e) => {return Err(e.in_file(chunk_name));}
+            Ok(toks) => toks
+        };
+        let mut statements = Vec::new();
+        let ast = match parser::parse_tokens(&toks[..], &mut statements) {
+            Err(e) => {return Err(e.in_file(chunk_name));}
+            Ok(v) => v
+        };
+        evaluate(&ast, source_file, &mut self.env, &mut self.defs)
+    }
+
+    //Evaluates a line of REPL input. Returns the value of a trailing bare expression, unless it's nil.
+    pub fn eval_line(&mut self, source: &str) -> Result<Option<Value>, Error> {
+        match try!(self.eval_str(source, "<stdin>")) {
+            Value::Nil => Ok(None),
+            v => Ok(Some(v))
+        }
+    }
+
+    pub fn interpret_file(&mut self, filename: &str) -> Result<&mut HashMap<Value, Value>, Error> {
         let path = &Path::new(filename);
         let mut file = match File::open(path) {
             Err(err) => {
-                {return interpreter_failure(format!("couldn't open {}: {}", path.display(), err));}
+                {return Err(Error::io(format!("couldn't open {}: {}", path.display(), err)));}
             },
             Ok(file) => file,
         };
         let mut file_string = String::new();
         match file.read_to_string(&mut file_string) {
-            Err(err) => {return interpreter_failure(format!("couldn't read {}: {}", path.display(), err));},
+            Err(err) => {return Err(Error::io(format!("couldn't read {}: {}", path.display(), err)));},
             Ok(_) => ()
         }
-        let lex_result = self.lexer.lex(file_string);
-        let ast = match lex_result {
-            Err(s) => {return Err(s);}
-            Ok(toks) => {
-                let parse_result = parser::parse_tokens(&toks[..], &mut self.statements);
-                match parse_result {
-                    Ok(v) => v,
-                    Err(s) => {return interpreter_failure(format!("failed to parse: {}", s));}
-                }
+        try!(self.eval_str(&file_string, filename));
+        Ok(&mut self.defs)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{env, fs};
+    use super::Interpreter;
+    use evaluator::value::{Value, FloatWrap};
+    use evaluator::clip::{Clip, ClipHolder};
+    use evaluator::environment::Environment;
+    use evaluator::hooks::display_string;
+    use evaluator::iter_clip::{iterator_for, next_value};
+    use error::Error;
+
+    #[test]
+    fn test_eval_str_result() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.eval_str("1 + 2", "test").ok().unwrap(), Value::Int(3));
+        assert_eq!(interpreter.eval_str("var x = 3", "test").ok().unwrap(), Value::Nil);
+    }
+
+    #[test]
+    fn test_eval_str_keeps_state() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("var x = 3", "first").ok().unwrap();
+        interpreter.eval_str("let add = fn(a) -> ret { ret = a + x }", "second").ok().unwrap();
+        interpreter.eval_str("x = 10", "third").ok().unwrap();
+        assert_eq!(interpreter.eval_str("add(5)", "fourth").ok().unwrap(), Value::Int(15));
+    }
+
+    #[test]
+    fn test_eval_str_error_chunk_name() {
+        let mut interpreter = Interpreter::new();
+        match interpreter.eval_str("var y = z", "my_chunk") {
+            Err(Error::Runtime{span: Some(span), ..}) => assert_eq!(span.file, "my_chunk"),
+            _ => assert!(false)
+        }
+    }
+
+    #[test]
+    fn test_comprehension() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let c = ${\n\
+                                for x in [1, 2, 3, 4] do\n\
+                                    if x % 2 == 0 do\n\
+                                        @x: x * 10\n\
+                                    end\n\
+                                end\n\
+                                done: true\n\
+                              }", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("(c[2], c[4], c[3], c.done)", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(20), Value::Int(40), Value::Nil, Value::Bool(true)]));
+    }
+
+    #[test]
+    fn test_try_catch() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("var caught = nil\n\
+                              let fail = fn(v) { error(v) }\n\
+                              for i in range(3) do\n\
+                                  try\n\
+                                      for j in range(3) do\n\
+                                          fail((i, j))\n\
+                                      end\n\
+                                  catch e\n\
+                                      caught = e.value\n\
+                                  end\n\
+                              end", "test").ok().unwrap();
+        assert_eq!(interpreter.get_var("caught").unwrap(),
+                   Value::Tuple(vec![Value::Int(2), Value::Int(0)]));
+        interpreter.eval_str("let ok, let e = pcall(fail, \"oops\")", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("(ok, e.message)", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Bool(false), Value::String("oops".to_string())]));
+        //Nothing from the failed plays should be left behind
+        assert_eq!(interpreter.eval_str("pcall(fn(a, b) -> c { c = a + b }, 1, 2)", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Bool(true), Value::Int(3)]));
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut interpreter = Interpreter::new();
+        let converted = interpreter.eval_str("(int(\"12\"), int(\"x\"), float(\"0.5\"), type(2.0))", "test");
+        assert_eq!(converted.ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(12), Value::Nil, Value::Float(FloatWrap::new(0.5)),
+                                     Value::String("float".to_string())]));
+        interpreter.eval_str("let c = ${name: \"c\"}", "test").ok().unwrap();
+        interpreter.eval_str("c.items = [c, \"x\"]", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("repr(c)", "test").ok().unwrap(),
+                   Value::String("${items: [${...}, \"x\"], name: \"c\"}".to_string()));
+    }
+
+    #[test]
+    fn test_display_clips() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let c = ${name: \"c\"\n f: fn(a) -> b { b = a }}", "test").ok().unwrap();
+        interpreter.eval_str("c.self = c", "test").ok().unwrap();
+        let c = interpreter.get_var("c").unwrap();
+        assert_eq!(c.to_string(), "${f: fn(a) -> b, name: \"c\", self: ${...}}");
+        interpreter.eval_str("c.__str = fn(self) -> s { s = self.name }", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("str((c, ${inner: c}))", "test").ok().unwrap(),
+                   Value::String("(c, ${inner: c})".to_string()));
+        assert_eq!(interpreter.eval_str("str((${for i in range(0) do @i: i end}, ${let x = 1}))", "test").ok().unwrap(),
+                   Value::String("(${}, ${})".to_string()));
+    }
+
+    #[test]
+    fn test_print_from_method() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("var shown = nil\n\
+                              let c = ${\n\
+                                  name: \"c\"\n\
+                                  show: fn(self) { shown = str(self) }\n\
+                              }\n\
+                              c|show()", "test").ok().unwrap();
+        assert_eq!(interpreter.get_var("shown").unwrap(), Value::String("${name: \"c\", show: ${...}}".to_string()));
+    }
+
+    #[test]
+    fn test_proto_cycle_from_body() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("var b = ${x: 1}\n\
+                              var a = fn() { proto: b }\n\
+                              b.proto = a", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("a()", "test").err().unwrap().message(),
+                   "can't set `proto` to a clip that inherits from this one");
+        assert_eq!(interpreter.eval_str("(a.x, b.missing, \"missing\" in b)", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Nil, Value::Nil, Value::Bool(false)]));
+    }
+
+    #[test]
+    fn test_failed_hooks_pop_frames() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let c = ${__str: fn(self) -> s { error(\"no str\") }\n\
+                              iter: fn(self) -> it { error(\"no iter\") }}", "test").ok().unwrap();
+        let c = interpreter.get_var("c").unwrap();
+        let depth = interpreter.env.frame_depth();
+        assert!(display_string(&c, &mut interpreter.env).is_err());
+        assert_eq!(interpreter.env.frame_depth(), depth);
+        assert!(iterator_for(c, &mut interpreter.env).is_err());
+        assert_eq!(interpreter.env.frame_depth(), depth);
+        interpreter.eval_str("let it = ${next: fn(self) -> r { error(\"no next\") }}", "test").ok().unwrap();
+        let it = interpreter.get_var("it").unwrap();
+        assert!(next_value(&it, &mut interpreter.env).is_err());
+        assert_eq!(interpreter.env.frame_depth(), depth);
+    }
+
+    fn float(f: f64) -> Value {
+        Value::Float(FloatWrap::new(f))
+    }
+
+    #[test]
+    fn test_mixed_arithmetic() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.eval_str("(1 + 0.5, 7 / 2, 7 / 2.0, -7 % 3, 2 ^ -1, (1, 2.5) * (2, 2))", "test")
+                              .ok().unwrap(),
+                   Value::Tuple(vec![float(1.5), Value::Int(3), float(3.5), Value::Int(-1), float(0.5),
+                                     Value::Tuple(vec![Value::Int(2), float(5.0)])]));
+        assert_eq!(interpreter.eval_str("(2 == 2.0, 1 < 1.5, (1, 2) == (1.0, 2), (1, 2) == (1, 2, 3))", "test")
+                              .ok().unwrap(),
+                   Value::Tuple(vec![Value::Bool(true), Value::Bool(true), Value::Bool(true), Value::Bool(false)]));
+        for source in ["1 / 0", "1 % 0", "(1, 2) / (1, 0)"].iter() {
+            match interpreter.eval_str(source, "test") {
+                Err(Error::Runtime{message, ..}) => assert!(message.contains("divisor of 0")),
+                _ => assert!(false)
             }
+        }
+        assert_eq!(interpreter.eval_str("1.0 / 0 == math.inf", "test").ok().unwrap(), Value::Bool(true));
+    }
+
+    fn big(s: &str) -> Value {
+        Value::BigInt(s.parse().unwrap())
+    }
+
+    #[test]
+    fn test_big_ints() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let max = 9223372036854775807\nlet min = -max - 1", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("(max + 1, min - 1, max * 2, 3 ^ 40, -min)", "test").ok().unwrap(),
+                   Value::Tuple(vec![big("9223372036854775808"), big("-9223372036854775809"),
+                                     big("18446744073709551614"), big("12157665459056928801"),
+                                     big("9223372036854775808")]));
+        assert_eq!(interpreter.eval_str("(min / -1, min % -1)", "test").ok().unwrap(),
+                   Value::Tuple(vec![big("9223372036854775808"), Value::Int(0)]));
+        //Results that fit in an i64 again are plain ints
+        assert_eq!(interpreter.eval_str("((max + 1) - 1, (max * 4) / 4, 2 ^ 64 / 2 ^ 60)", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(9223372036854775807), Value::Int(9223372036854775807),
+                                     Value::Int(16)]));
+        assert!(interpreter.eval_str("(10 ^ 1000) ^ 1000000", "test").is_err());
+        assert!(interpreter.eval_str("(2 ^ 70) / 0", "test").is_err());
+    }
+
+    #[test]
+    fn test_math_functions() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.eval_str("(math.abs(-3), math.abs(-2.5), math.min(3, 1.5, 2), math.max(1, 4))", "test")
+                              .ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(3), float(2.5), float(1.5), Value::Int(4)]));
+        assert_eq!(interpreter.eval_str("(math.floor(-1.5), math.round(2), math.sqrt(9), math.log(100, 10))", "test")
+                              .ok().unwrap(),
+                   Value::Tuple(vec![float(-2.0), Value::Int(2), float(3.0), float(2.0)]));
+        assert_eq!(interpreter.eval_str("(math.int(-2.7), math.float(3), math.nan == math.nan)", "test")
+                              .ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(-2), float(3.0), Value::Bool(false)]));
+        assert!(interpreter.eval_str("math.int(math.nan)", "test").is_err());
+        assert!(interpreter.eval_str("math.sqrt(\"4\")", "test").is_err());
+        assert!(interpreter.eval_str("math.min()", "test").is_err());
+    }
+
+    fn strings(values: &[&str]) -> Value {
+        Value::Tuple(values.iter().map(|s| Value::String(s.to_string())).collect())
+    }
+
+    #[test]
+    fn test_string_indexing() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let s = \"añb→ç\"", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("(s[1], s[-1], s[-5], s|sub(-3), s|sub(1, -1), s|sub(-9, 2))", "test")
+                              .ok().unwrap(),
+                   strings(&["ñ", "ç", "a", "b→ç", "ñb→", "añ"]));
+        assert_eq!(interpreter.eval_str("(s[5], s[-6], s|sub(4, 2))", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Nil, Value::Nil, Value::String(String::new())]));
+        assert_eq!(interpreter.eval_str("(s|find(\"→\"), s|find(\"b\", -3), s|find(\"a\", 1), s|len())", "test")
+                              .ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(3), Value::Int(2), Value::Nil, Value::Int(5)]));
+    }
+
+    #[test]
+    fn test_string_format() {
+        let mut interpreter = Interpreter::new();
+        assert_eq!(interpreter.eval_str("(string.format(2.5, \"<6.2\"), string.format(\"é\", \">3\"), \
+                                         string.format(7, \"03\"), string.format(\"añbç\", \"^6.3\"))", "test")
+                              .ok().unwrap(),
+                   strings(&["2.50  ", "  é", "007", " añb  "]));
+        assert!(interpreter.eval_str("string.format(1, \"x\")", "test").is_err());
+    }
+
+    #[test]
+    fn test_string_join_and_repeat() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let p = ${__str: fn(self) -> s { s = \"p\" }}", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("\", \"|join((p, 1, \"a\"))", "test").ok().unwrap(),
+                   Value::String("p, 1, a".to_string()));
+        assert!(interpreter.eval_str("\"ab\"|repeat(9223372036854775807)", "test").is_err());
+        assert_eq!(interpreter.eval_str("\"\"|repeat(9223372036854775807)", "test").ok().unwrap(),
+                   Value::String(String::new()));
+    }
+
+    #[test]
+    fn test_repr_from_method() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("var shown = nil\n\
+                              let c = ${\n\
+                                  n: 1\n\
+                                  show: fn(self) { shown = repr(self) }\n\
+                              }\n\
+                              c|show()", "test").ok().unwrap();
+        assert_eq!(interpreter.get_var("shown").unwrap(), Value::String("${n: 1, show: ${...}}".to_string()));
+    }
+
+    #[test]
+    fn test_register_raw_fn() {
+        let mut interpreter = Interpreter::new();
+        interpreter.register_fn("count", |args: &[Value]| Ok(Value::Int(args.len() as i64)));
+        assert_eq!(interpreter.eval_str("count(1, 2, 3)", "test").ok().unwrap(), Value::Int(3));
+    }
+
+    #[test]
+    fn test_register_typed_fn() {
+        let mut interpreter = Interpreter::new();
+        interpreter.register_fn("add", |a: i64, b: i64| a + b);
+        interpreter.register_fn("greet", |name: Option<String>| {
+            format!("hi {}", name.unwrap_or("nobody".to_string()))
+        });
+        interpreter.register_fn("div", |a: i64, b: i64| {
+            if b == 0 {
+                Err(Error::runtime("division by zero"))
+            } else {
+                Ok(a / b)
+            }
+        });
+        assert_eq!(interpreter.eval_str("add(2, 3)", "test").ok().unwrap(), Value::Int(5));
+        assert_eq!(interpreter.eval_str("greet(nil)", "test").ok().unwrap(),
+                   Value::String("hi nobody".to_string()));
+        assert_eq!(interpreter.eval_str("div(7, 2)", "test").ok().unwrap(), Value::Int(3));
+        let mut message = |source| {
+            interpreter.eval_str(source, "test").err().unwrap().message().to_string()
+        };
+        assert_eq!(message("add(1)"), "Wrong number of args for `add`: expected 2 but got 1");
+        assert_eq!(message("add(1, \"2\")"), "Bad arg 2 for `add`: expected int but found string");
+        assert_eq!(message("div(1, 0)"), "division by zero");
+    }
+
+    #[test]
+    fn test_register_fn_with_sequences() {
+        let mut interpreter = Interpreter::new();
+        interpreter.register_fn("sum", |vals: Vec<i64>| vals.iter().fold(0, |a, b| a + b));
+        assert_eq!(interpreter.eval_str("(sum([1, 2, 3]), sum((4, 5)), sum([]))", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::Int(6), Value::Int(9), Value::Int(0)]));
+        let mut message = |source| {
+            interpreter.eval_str(source, "test").err().unwrap().message().to_string()
         };
-        match evaluate(&ast, &mut self.defs) {
-            Ok(_) => Ok(&mut self.defs),
-            Err(e) => Err(e)
+        assert_eq!(message("sum([1, \"2\"])"), "Bad arg 1 for `sum`: expected int but found string");
+        assert_eq!(message("sum(${a: 1})"), "Bad arg 1 for `sum`: expected tuple or list but found clip");
+    }
+
+    #[test]
+    fn test_vars_and_defs() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_var("x", Value::Int(4)).ok().unwrap();
+        interpreter.eval_str("var y = x * 2\nz: y + 1", "test").ok().unwrap();
+        assert_eq!(interpreter.get_var("y"), Some(Value::Int(8)));
+        assert_eq!(interpreter.get_def(&Value::String("z".to_string())), Value::Int(9));
+        interpreter.eval_str("let w = 1", "test").ok().unwrap();
+        assert!(interpreter.set_var("w", Value::Int(2)).is_err());
+    }
+
+    #[test]
+    fn test_play_script_clip() {
+        let mut interpreter = Interpreter::new();
+        interpreter.eval_str("let sub = fn(a, b) -> ret { ret = a - b }", "test").ok().unwrap();
+        let result = interpreter.play_var("sub", vec![Value::Int(5), Value::Int(3)]);
+        assert_eq!(result.ok().unwrap(), Value::Int(2));
+        assert!(interpreter.play_var("missing", Vec::new()).is_err());
+        assert!(interpreter.play(&Value::Int(1), Vec::new()).is_err());
+        interpreter.eval_str("let double = ${__call: fn(self, x) -> r { r = x * 2 }}\n\
+                              let fail = fn() { error(\"oops\") }", "test").ok().unwrap();
+        assert_eq!(interpreter.play_var("double", vec![Value::Int(4)]).ok().unwrap(), Value::Int(8));
+        let err = interpreter.play_var("fail", Vec::new()).err().unwrap().to_string();
+        assert!(err.ends_with("Stack trace:\n    played at <host>:1:1"));
+    }
+
+    #[derive(Debug)]
+    struct Point {
+        x: i64,
+        y: i64
+    }
+
+    impl Clip for Point {
+        fn get(&self, key: &Value) -> Value {
+            match *key {
+                Value::String(ref s) if s == "x" => Value::Int(self.x),
+                Value::String(ref s) if s == "y" => Value::Int(self.y),
+                _ => Value::Nil
+            }
+        }
+
+        fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+            match (key, value) {
+                (Value::String(ref s), Value::Int(i)) if s == "x" => self.x = i,
+                (Value::String(ref s), Value::Int(i)) if s == "y" => self.y = i,
+                _ => {return Err(Error::runtime("points only have int x and y"));}
+            }
+            Ok(())
+        }
+
+        fn contains(&self, key: &Value) -> bool {
+            self.get(key) != Value::Nil
         }
+
+        fn play(&mut self, _: Vec<Value>, _: &mut Environment) -> Result<Value, Error> {
+            Err(Error::runtime("can't play a point"))
+        }
+    }
+
+    #[test]
+    fn test_host_object() {
+        let mut interpreter = Interpreter::new();
+        let point = ClipHolder::new(Box::new(Point{x: 1, y: 2}));
+        interpreter.set_var("p", Value::Clip(point.clone())).ok().unwrap();
+        assert_eq!(interpreter.eval_str("p.x + p[\"y\"]", "test").ok().unwrap(), Value::Int(3));
+        point.set(Value::String("x".to_string()), Value::Int(10)).ok().unwrap();
+        assert_eq!(interpreter.eval_str("p.x", "test").ok().unwrap(), Value::Int(10));
+        assert!(point.set(Value::String("z".to_string()), Value::Int(0)).is_err());
+    }
+
+    //Gives back how many plays are in progress when it's played
+    #[derive(Debug)]
+    struct CallDepth;
+
+    impl Clip for CallDepth {
+        fn get(&self, _: &Value) -> Value {
+            Value::Nil
+        }
+
+        fn set(&mut self, _: Value, _: Value) -> Result<(), Error> {
+            Err(Error::runtime("can't set a def on a probe"))
+        }
+
+        fn contains(&self, _: &Value) -> bool {
+            false
+        }
+
+        fn play(&mut self, _: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
+            Ok(Value::Int(environment.call_depth() as i64))
+        }
+    }
+
+    #[test]
+    fn test_pcall_plays_like_a_call() {
+        let mut interpreter = Interpreter::new();
+        interpreter.set_var("depth", Value::Clip(ClipHolder::new(Box::new(CallDepth)))).ok().unwrap();
+        interpreter.eval_str("let c = ${__call: fn(self, x) -> r { r = x * 2 }}", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("(pcall(c, 4), depth(), pcall(depth))", "test").ok().unwrap(),
+                   Value::Tuple(vec![
+                       Value::Tuple(vec![Value::Bool(true), Value::Int(8)]),
+                       Value::Int(1),
+                       Value::Tuple(vec![Value::Bool(true), Value::Int(2)])
+                   ]));
+    }
+
+    #[test]
+    fn test_files() {
+        let dir = env::temp_dir().join("housecat_test_files");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("test.txt").to_string_lossy().into_owned();
+        let mut interpreter = Interpreter::new();
+        interpreter.set_var("dir", Value::String(dir.to_string_lossy().into_owned())).ok().unwrap();
+        interpreter.set_var("path", Value::String(path.clone())).ok().unwrap();
+        interpreter.eval_str("var f = io.open(path, \"w\")\n\
+                              f|write(\"a\\n\")\n\
+                              f|write((1, \"b\"))\n\
+                              f|close()\n\
+                              f = io.open(path, \"a\")\n\
+                              f|write(\"\\nc\")\n\
+                              f|write(${__str: fn(self) -> s { s = \"d\" }})\n\
+                              f|close()", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("f = io.open(path, \"r\")\n\
+                                         let all = f|read()\n\
+                                         f|close()\n\
+                                         all", "test").ok().unwrap(),
+                   Value::String("a\n(1, b)\ncd".to_string()));
+        assert_eq!(interpreter.eval_str("f = io.open(path, \"r\")\n\
+                                         let first = f|read_line()\n\
+                                         var rest = \"\"\n\
+                                         for line in f|lines() do rest = rest + line + \";\" end\n\
+                                         (first, rest, f|read_line())", "test").ok().unwrap(),
+                   Value::Tuple(vec![Value::String("a".to_string()), Value::String("(1, b);cd;".to_string()),
+                                     Value::Nil]));
+        assert_eq!(interpreter.eval_str("io.list_dir(dir)[0]", "test").ok().unwrap(),
+                   Value::String("test.txt".to_string()));
+
+        let closed = format!("{} is closed", path);
+        let missing = format!("couldn't open {}.missing", path);
+        {
+            let mut message = |source| {
+                interpreter.eval_str(source, "test").err().unwrap().message().to_string()
+            };
+            assert_eq!(message("f|write(\"x\")"), format!("{} wasn't opened for writing", path));
+            assert_eq!(message("f|close()\nf|read()"), closed);
+            assert_eq!(message("f|read_line()"), closed);
+            assert_eq!(message("io.open(path, \"x\")"), "invalid file mode `x`, expected r, w or a");
+            assert!(message("io.open(path + \".missing\", \"r\")").starts_with(&missing[..]));
+        }
+
+        interpreter.eval_str("io.remove(path)", "test").ok().unwrap();
+        assert_eq!(interpreter.eval_str("io.exists(path)", "test").ok().unwrap(), Value::Bool(false));
+        fs::remove_dir(&dir).unwrap();
     }
 }
@@ -1,6 +1,6 @@
 use token::{Token, Tok};
 use regex::Regex;
-use utils::get_caret_string;
+use error::{Error, Span};
 
 
 static SYMBOL_SPECS: &'static [(&'static str, Token<'static>)] = &[
@@ -61,7 +61,11 @@ fn match_int(line_slice: &str) -> Option<(Token, usize)> {
         None => {return None}
     };
     let token_slice = &line_slice[start..end];
-    return Some((Token::Int(token_slice.parse().unwrap()), end));
+    //Anything that doesn't fit in an i64 becomes a big int
+    match token_slice.parse() {
+        Ok(i) => Some((Token::Int(i), end)),
+        Err(_) => Some((Token::BigInt(token_slice.parse().unwrap()), end))
+    }
 }
 
 fn match_keyword(line_slice: &str) -> Option<(Token, usize)> {
@@ -87,20 +91,129 @@ fn match_keyword(line_slice: &str) -> Option<(Token, usize)> {
         "true" => Token::Bool(true),
         "false" => Token::Bool(false),
         "do" => Token::Do,
+        "break" => Token::Break,
+        "continue" => Token::Continue,
+        "try" => Token::Try,
+        "catch" => Token::Catch,
         s => Token::Ident(s)
     };
     return Some((tok, end));
 }
 
-fn match_string(line_slice: &str) -> Option<(Token, usize)> {
-    let re = Regex::new(r#"^"(?:[^"\\]|\\.)*""#).unwrap();
-    let (start,end) = match re.find(line_slice) {
-        Some(range) => range,
-        None => {return None}
+//Line, column and message for something that couldn't be lexed
+type LexFailure = (usize, usize, String);
+
+//Piece of a string literal: text with its escapes handled, or the line and columns of an
+//expression inside `{}` that gets interpolated
+enum StringPart<'a> {
+    Text(String),
+    Expr(&'a str, usize, usize, usize)
+}
+
+//Reads the escape that starts with the `\` at `idx`, giving back the char and its length
+fn read_escape(line: &str, line_no: usize, idx: usize) -> Result<(char, usize), LexFailure> {
+    let rest = &line[idx + 1..];
+    let c = match rest.chars().next() {
+        Some(c) => c,
+        None => {return Err((line_no, idx, "strings can't end with a `\\`".to_string()));}
     };
-    let trimmed_slice = &line_slice[start + 1..end - 1];
-    let escaped = trimmed_slice.replace(r#"\""#, "\"").replace(r"\\", r"\");
-    return Some((Token::String(escaped), end));
+    let simple = match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        '0' => Some('\0'),
+        '\\' => Some('\\'),
+        '"' => Some('"'),
+        '\'' => Some('\''),
+        '{' => Some('{'),
+        '}' => Some('}'),
+        _ => None
+    };
+    if let Some(escaped) = simple {
+        return Ok((escaped, 1 + c.len_utf8()));
+    }
+    if c == 'u' && rest[1..].starts_with("{") {
+        if let Some(close) = rest.find('}') {
+            let code = u32::from_str_radix(&rest[2..close], 16).ok().and_then(char::from_u32);
+            return match code {
+                Some(escaped) => Ok((escaped, close + 2)),
+                None => Err((line_no, idx, format!("invalid unicode escape `\\{}`", &rest[..close + 1])))
+            };
+        }
+        return Err((line_no, idx, "unicode escapes must be closed with `}`".to_string()));
+    }
+    Err((line_no, idx, format!("unknown escape `\\{}` in string", c)))
+}
+
+//Finds the `}` that closes the interpolation opened at `open`, skipping over any strings in it
+fn find_interp_end(line: &str, line_no: usize, open: usize) -> Result<usize, LexFailure> {
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, c) in line[open..].char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => ()
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => depth += 1,
+            '}' => {
+                depth -= 1;
+                if depth == 0 {
+                    if idx == 1 {
+                        return Err((line_no, open, "`{}` in a string needs an expression".to_string()));
+                    }
+                    return Ok(open + idx);
+                }
+            }
+            _ => ()
+        }
+    }
+    Err((line_no, open, "`{` in a string must be closed with `}` on the same line".to_string()))
+}
+
+//Reads string contents from `start` up to the closing quotes. Gives back the column after the
+//quotes, or None if the line ran out first, which is only allowed for long strings.
+fn scan_string<'a>(line: &'a str, line_no: usize, start: usize, quotes: &str,
+                   parts: &mut Vec<StringPart<'a>>) -> Result<Option<usize>, LexFailure> {
+    let mut text = String::new();
+    let mut idx = start;
+    while idx < line.len() {
+        if line[idx..].starts_with(quotes) {
+            parts.push(StringPart::Text(text));
+            return Ok(Some(idx + quotes.len()));
+        }
+        let c = line[idx..].chars().next().unwrap();
+        match c {
+            '\\' => {
+                let (escaped, len) = try!(read_escape(line, line_no, idx));
+                text.push(escaped);
+                idx += len;
+            }
+            '{' => {
+                let end = try!(find_interp_end(line, line_no, idx));
+                parts.push(StringPart::Text(text));
+                text = String::new();
+                parts.push(StringPart::Expr(line, line_no, idx + 1, end));
+                idx = end + 1;
+            }
+            '}' => {
+                return Err((line_no, idx, "use `\\}` for a `}` in a string".to_string()));
+            }
+            c => {
+                text.push(c);
+                idx += c.len_utf8();
+            }
+        }
+    }
+    parts.push(StringPart::Text(text));
+    Ok(None)
 }
 
 impl<'a> Lexer<'a> {
@@ -111,36 +224,128 @@ impl<'a> Lexer<'a> {
         }
     }
 
-    pub fn lex(&'a mut self, s: String) -> Result<&Vec<Tok<'a>>, String> {
+    pub fn lex(&'a mut self, s: String) -> Result<&Vec<Tok<'a>>, Error> {
         let mut char_index = 0usize;
         self.input = s;
+        let lines: Vec<&'a str> = self.input.lines().collect();
         let mut line_index = 0;
-        for l in self.input.lines() {
-            let res = Lexer::lex_line(l, line_index, &mut char_index, &mut self.toks);
+        let mut start_col = 0;
+        while line_index < lines.len() {
+            let l = lines[line_index];
+            let res = Lexer::lex_line(l, line_index, start_col, l.len(), &mut char_index, &mut self.toks);
+            let res = match res {
+                //A long string carries on over the next lines, so pick up after it ends
+                Ok(Some(col)) => {
+                    Lexer::lex_long_string(&lines, line_index, col, &mut char_index, &mut self.toks)
+                }
+                Ok(None) => Ok((line_index + 1, 0)),
+                Err(failure) => Err(failure)
+            };
             match res {
-                Ok(()) => {char_index += 1;},
-                Err(col) => {
-                    return Err(
-                        format!(
-                            "LEXING FAILURE at {}:{} invalid character {}\n{}\n{}",
-                            line_index + 1,
-                            col + 1,
-                            l.chars().nth(col).unwrap(),
-                            l,
-                            get_caret_string(col)
-                        )
-                    );
+                Ok((next_line, next_col)) => {
+                    if next_line != line_index {
+                        char_index += 1;
+                    }
+                    line_index = next_line;
+                    start_col = next_col;
+                }
+                Err((line, col, message)) => {
+                    return Err(Error::Lex {
+                        span: Span::new("", line, col, lines[line]),
+                        message: message
+                    });
                 }
             }
-            line_index += 1;
         }
         self.toks.push(Tok{token: Token::Eof, line: line_index, col: 0, line_string: "", char_index: char_index});
         Ok(&self.toks)
     }
 
-    fn lex_line(line: &'a str, line_no: usize, char_index: &mut usize, toks: &mut Vec<Tok<'a>>) -> Result<(), usize> {
-        let mut line_slice = line;
-        let mut col = 0usize;
+    //Lexes a `"""` string that starts at `col`, giving back the line and column just after it
+    fn lex_long_string(lines: &[&'a str], line_no: usize, col: usize, char_index: &mut usize,
+                       toks: &mut Vec<Tok<'a>>) -> Result<(usize, usize), LexFailure> {
+        let mut parts = Vec::new();
+        let mut cur_line = line_no;
+        let mut start = col + 3;
+        //A line break right after the opening quotes isn't part of the string
+        if start == lines[line_no].len() && line_no + 1 < lines.len() {
+            cur_line += 1;
+            start = 0;
+        }
+        loop {
+            let line = lines[cur_line];
+            if let Some(end) = try!(scan_string(line, cur_line, start, "\"\"\"", &mut parts)) {
+                try!(Lexer::push_string(parts, lines[line_no], line_no, col, *char_index, toks));
+                if cur_line == line_no {
+                    *char_index += end - col;
+                } else {
+                    *char_index += lines[line_no].len() - col;
+                    for l in lines[line_no + 1..cur_line].iter() {
+                        *char_index += l.len() + 1;
+                    }
+                    *char_index += end;
+                }
+                return Ok((cur_line, end));
+            }
+            cur_line += 1;
+            if cur_line == lines.len() {
+                return Err((line_no, col, "`\"\"\"` strings must be closed with `\"\"\"`".to_string()));
+            }
+            parts.push(StringPart::Text("\n".to_string()));
+            start = 0;
+        }
+    }
+
+    //Pushes a plain string token, or the tokens for an interpolated string if it has any `{}`s
+    fn push_string(parts: Vec<StringPart<'a>>, line: &'a str, line_no: usize, col: usize, char_index: usize,
+                   toks: &mut Vec<Tok<'a>>) -> Result<(), LexFailure> {
+        let tok = |token| Tok{token: token, line: line_no, col: col, line_string: line, char_index: char_index};
+        let has_exprs = parts.iter().any(|p| match *p {
+            StringPart::Expr(..) => true,
+            _ => false
+        });
+        if !has_exprs {
+            let mut text = String::new();
+            for part in parts.into_iter() {
+                if let StringPart::Text(s) = part {
+                    text.push_str(&s[..]);
+                }
+            }
+            toks.push(tok(Token::String(text)));
+            return Ok(());
+        }
+        toks.push(tok(Token::InterpStart));
+        for part in parts.into_iter() {
+            match part {
+                StringPart::Text(s) => {
+                    if s.len() > 0 {
+                        toks.push(tok(Token::String(s)));
+                    }
+                }
+                StringPart::Expr(expr_line, expr_line_no, start, end) => {
+                    let mut expr_index = char_index;
+                    toks.push(Tok{token: Token::OpenCurly, line: expr_line_no, col: start - 1,
+                                  line_string: expr_line, char_index: expr_index});
+                    if let Some(long_col) = try!(Lexer::lex_line(expr_line, expr_line_no, start, end,
+                                                                 &mut expr_index, toks)) {
+                        return Err((expr_line_no, long_col,
+                                    "`\"\"\"` strings can't go inside `{}`".to_string()));
+                    }
+                    toks.push(Tok{token: Token::CloseCurly, line: expr_line_no, col: end,
+                                  line_string: expr_line, char_index: expr_index});
+                }
+            }
+        }
+        toks.push(tok(Token::InterpEnd));
+        Ok(())
+    }
+
+    //Lexes the part of a line from `start` to `end`. Stops early if a `"""` string starts, and
+    //gives back its column.
+    fn lex_line(line: &'a str, line_no: usize, start: usize, end: usize, char_index: &mut usize,
+                toks: &mut Vec<Tok<'a>>) -> Result<Option<usize>, LexFailure> {
+        let mut line_slice = &line[start..end];
+        let mut col = start;
         let mut match_end = 0usize;
         while line_slice.len() > 0 {
             let mut found_token = false;
@@ -162,6 +367,23 @@ impl<'a> Lexer<'a> {
                 }
                 None => ()
             };
+            //Lex strings
+            if !found_token && line_slice.starts_with("\"") {
+                if line_slice.starts_with("\"\"\"") {
+                    return Ok(Some(col));
+                }
+                let mut parts = Vec::new();
+                match try!(scan_string(&line[..end], line_no, col + 1, "\"", &mut parts)) {
+                    Some(string_end) => {
+                        try!(Lexer::push_string(parts, line, line_no, col, *char_index, toks));
+                        match_end = string_end - col;
+                        found_token = true;
+                    }
+                    None => {
+                        return Err((line_no, col, "strings must be closed with `\"` on the same line".to_string()));
+                    }
+                }
+            }
             //Lex symbols
             if !found_token {
                 for &(s, ref tok_type) in SYMBOL_SPECS.iter() {
@@ -189,8 +411,7 @@ impl<'a> Lexer<'a> {
                 let mut funcs: Vec<Box<Fn(&str) -> Option<(Token, usize)>>> = vec![
                     Box::new(match_float),
                     Box::new(match_int),
-                    Box::new(match_keyword),
-                    Box::new(match_string)
+                    Box::new(match_keyword)
                 ];
                 for f in funcs.iter_mut() {
                     match f(line_slice) {
@@ -207,7 +428,8 @@ impl<'a> Lexer<'a> {
 
             //No token was found, which means that something was invalid
             if !found_token {
-                return Err(col);
+                let c = line_slice.chars().next().unwrap();
+                return Err((line_no, col, format!("invalid character {}", c)));
             }
 
             //Push the column index to the end of what we just read
@@ -215,7 +437,7 @@ impl<'a> Lexer<'a> {
             *char_index += match_end;
             line_slice = &line_slice[match_end..];
         }
-        Ok(())
+        Ok(None)
     }
 }
 
@@ -240,6 +462,22 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_loop_keywords() {
+        match_tokens(
+            "break continue breaks continued",
+            vec![Token::Break, Token::Continue, Token::Ident("breaks"), Token::Ident("continued")]
+        );
+    }
+
+    #[test]
+    fn test_try_keywords() {
+        match_tokens(
+            "try catch e end",
+            vec![Token::Try, Token::Catch, Token::Ident("e"), Token::End]
+        );
+    }
+
     #[test]
     fn test_ambiguous() {
         match_tokens(
@@ -283,7 +521,7 @@ mod test {
     fn test_invalid_char() {
         let mut lexer = Lexer::new();
         match lexer.lex("this is & invalid".to_string()){
-            Err(s) => assert_eq!("LEXING FAILURE at 1:9 invalid character &\nthis is & invalid\n        ^".to_string(), s),
+            Err(e) => assert_eq!("LEXING FAILURE at 1:9 invalid character &\nthis is & invalid\n        ^".to_string(), e.to_string()),
             _ => assert!(false)
         }
     }
@@ -356,6 +594,19 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_big_ints() {
+        let big = "123456789012345678901234567890".parse().unwrap();
+        match_tokens(
+            "9223372036854775807 9223372036854775808 123456789012345678901234567890",
+            vec![
+                Token::Int(9223372036854775807),
+                Token::BigInt("9223372036854775808".parse().unwrap()),
+                Token::BigInt(big)
+            ]
+        )
+    }
+
     #[test]
     fn test_strings() {
         match_tokens(
@@ -372,4 +623,59 @@ mod test {
             ]
         )
     }
+
+    #[test]
+    fn test_string_escapes() {
+        match_tokens(
+            r#""a\tb\nc\\ \u{e9}\u{1F600} \{x\}""#,
+            vec![Token::String("a\tb\nc\\ \u{e9}\u{1F600} {x}".to_string())]
+        );
+        let mut lexer = Lexer::new();
+        let err = lexer.lex(r#"var a = "bad \q escape""#.to_string()).err().unwrap();
+        assert_eq!(err.message(), "unknown escape `\\q` in string");
+        assert_eq!(err.span().unwrap().col, 13);
+    }
+
+    #[test]
+    fn test_long_strings() {
+        match_tokens(
+            "var a = \"\"\"\nfirst \"line\"\n  second\"\"\" + 1",
+            vec![
+                Token::Var,
+                Token::Ident("a"),
+                Token::Assign,
+                Token::String("first \"line\"\n  second".to_string()),
+                Token::Add,
+                Token::Int(1)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interpolation() {
+        match_tokens(
+            r#""Hello {name}, {f("x{1}")}!""#,
+            vec![
+                Token::InterpStart,
+                Token::String("Hello ".to_string()),
+                Token::OpenCurly,
+                Token::Ident("name"),
+                Token::CloseCurly,
+                Token::String(", ".to_string()),
+                Token::OpenCurly,
+                Token::Ident("f"),
+                Token::OpenParen,
+                Token::InterpStart,
+                Token::String("x".to_string()),
+                Token::OpenCurly,
+                Token::Int(1),
+                Token::CloseCurly,
+                Token::InterpEnd,
+                Token::CloseParen,
+                Token::CloseCurly,
+                Token::String("!".to_string()),
+                Token::InterpEnd
+            ]
+        );
+    }
 }
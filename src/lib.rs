@@ -12,5 +12,12 @@ mod utils;
 mod evaluator;
 mod interpreter;
 mod libhc;
+mod error;
 
 pub use interpreter::Interpreter;
+pub use error::{Error, Span};
+pub use evaluator::value::{Value, FloatWrap};
+pub use evaluator::clip::{Clip, ClipHolder};
+pub use evaluator::environment::Environment;
+pub use evaluator::convert::{FromValue, IntoValue};
+pub use evaluator::native_clip::{NativeFn, NativeResult, RawArgs};
@@ -2,7 +2,13 @@ use evaluator::value::Value;
 use evaluator::clip::{Clip, ClipHolder};
 use evaluator::standard_clip::StdClip;
 use evaluator::environment::Environment;
+use evaluator::source_map::{SourceFile, SourceMap};
+use evaluator::error_value::error_value;
+use evaluator::hooks::callee;
 use interpreter::Interpreter;
+use error::Error;
+use std::collections::HashMap;
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct Import;
@@ -13,12 +19,20 @@ impl<'a> Clip for Import {
         Value::Nil
     }
 
-    fn set(&mut self, key: Value, value: Value) -> Result<(), String> {
-        Err("Cannot set a def on import built-in".to_string())
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime("Cannot set a def on import built-in"))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        false
+    }
+
+    fn signature(&self) -> Option<String> {
+        Some("<native fn import>".to_string())
     }
 
     fn play(&mut self, args: Vec<Value>, environment: &mut Environment)
-         -> Result<Value, String> {
+         -> Result<Value, Error> {
         if args.len() == 1 {
             let mut interpreter = Interpreter::new();
             match args[0] {
@@ -29,14 +43,137 @@ impl<'a> Clip for Import {
                         Vec::new(),
                         Vec::new(),
                         Vec::new(),
+                        SourceMap::new(Rc::new(SourceFile::new(&s[..], "")), Vec::new()),
                         c
                     );
                     Ok(Value::Clip(ClipHolder::new(Box::new(new_clip))))
                 }
-                _ => Result::Err("Can only use a string parameter for `import`".to_string())
+                _ => Err(Error::runtime("Can only use a string parameter for `import`"))
             }
         } else {
-            Result::Err("Wrong number of args for `import`".to_string())
+            Err(Error::runtime("Wrong number of args for `import`"))
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct PCall;
+
+//Plays a clip with the rest of the args, giving back (true, result) if it succeeds or
+//(false, error) if it fails
+#[allow(unused_variables, dead_code)]
+impl Clip for PCall {
+    fn get(&self, key: &Value) -> Value {
+        Value::Nil
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime("Cannot set a def on pcall built-in"))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        false
+    }
+
+    fn signature(&self) -> Option<String> {
+        Some("<native fn pcall>".to_string())
+    }
+
+    fn play(&mut self, mut args: Vec<Value>, environment: &mut Environment)
+         -> Result<Value, Error> {
+        if args.len() == 0 {
+            return Err(Error::runtime("Wrong number of args for `pcall`: expected at least 1 but got 0"));
+        }
+        let value = args.remove(0);
+        let type_name = value.type_name();
+        let (mut clip, args) = match callee(value, args) {
+            Some(c) => c,
+            None => {
+                return Err(Error::runtime(format!("Bad arg 1 for `pcall`: expected clip but found {}",
+                                                  type_name)));
+            }
+        };
+        //The clip is played from where `pcall` was, so traces show that play too
+        let location = environment.current_call();
+        match environment.play(&mut clip, args, location) {
+            Ok(v) => Ok(Value::Tuple(vec![Value::Bool(true), v])),
+            Err(e) => Ok(Value::Tuple(vec![Value::Bool(false), error_value(e)]))
+        }
+    }
+}
+
+//error(value) fails with the value, which `try` and `pcall` hand back as the error's `value`
+pub fn error(args: &[Value]) -> Result<Value, Error> {
+    match args.len() {
+        1 => Err(Error::raised(args[0].clone())),
+        n => Err(Error::runtime(format!("Wrong number of args for `error`: expected 1 but got {}", n)))
+    }
+}
+
+fn copy_clip(clip: &ClipHolder) -> Result<ClipHolder, Error> {
+    match clip.borrow().copy() {
+        Some(copy) => Ok(ClipHolder::new(copy)),
+        None => Err(Error::runtime("can't clone a native clip without sharing its state"))
+    }
+}
+
+//Copies a clip's defs into a new clip. Anything that isn't a clip is already immutable,
+//so it comes back as it is.
+pub fn clone(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::Clip(ref c) => Ok(Value::Clip(try!(copy_clip(c)))),
+        v => Ok(v)
+    }
+}
+
+//Copies are tracked by the id of the original, so clips that refer to each other are
+//copied once and end up referring to each other's copies
+fn deep_copy(value: Value, copies: &mut HashMap<usize, ClipHolder>) -> Result<Value, Error> {
+    match value {
+        Value::Clip(c) => {
+            if let Some(copy) = copies.get(&c.id()) {
+                return Ok(Value::Clip(copy.clone()));
+            }
+            let copy = try!(copy_clip(&c));
+            copies.insert(c.id(), copy.clone());
+            //Prototypes hold behavior that's shared, so copies keep using the same one
+            let proto_key = Value::String("proto".to_string());
+            let keys = copy.borrow().keys();
+            for key in keys.into_iter().filter(|k| *k != proto_key) {
+                let inner = copy.get(&key);
+                let inner_copy = try!(deep_copy(inner, copies));
+                try!(copy.set(key, inner_copy));
+            }
+            Ok(Value::Clip(copy))
+        }
+        Value::Tuple(vals) => {
+            let mut result = Vec::with_capacity(vals.len());
+            for v in vals.into_iter() {
+                result.push(try!(deep_copy(v, copies)));
+            }
+            Ok(Value::Tuple(result))
+        }
+        v => Ok(v)
+    }
+}
+
+pub fn deepclone(value: Value) -> Result<Value, Error> {
+    deep_copy(value, &mut HashMap::new())
+}
+
+//New clip with the defs of `overrides` that looks up anything else in `base`
+pub fn extend(base: Value, overrides: Value) -> Result<Value, Error> {
+    match (base, overrides) {
+        (Value::Clip(base), Value::Clip(ref overrides)) => {
+            let result = try!(copy_clip(overrides));
+            try!(result.set(Value::String("proto".to_string()), Value::Clip(base)));
+            Ok(Value::Clip(result))
+        }
+        (Value::Clip(_), v) => {
+            Err(Error::runtime(format!("Bad arg 2 for `extend`: expected clip but found {}", v.type_name())))
+        }
+        (v, _) => {
+            Err(Error::runtime(format!("Bad arg 1 for `extend`: expected clip but found {}", v.type_name())))
         }
     }
 }
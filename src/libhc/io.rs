@@ -1,27 +1,243 @@
+use std::cell::RefCell;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::rc::Rc;
+
 use evaluator::value::Value;
-use evaluator::clip::Clip;
+use evaluator::clip::{Clip, ClipHolder};
 use evaluator::environment::Environment;
+use evaluator::hooks::display_string;
+use evaluator::native_clip::{NativeClip, native_value};
+use evaluator::list_clip::ListClip;
+use evaluator::iter_clip::IterClip;
+use error::Error;
+
+//Args as they're printed, separated by spaces
+fn joined(args: &[Value], environment: &mut Environment) -> Result<String, Error> {
+    let mut strings = Vec::with_capacity(args.len());
+    for arg in args.iter() {
+        strings.push(try!(display_string(arg, environment)));
+    }
+    Ok(strings.join(" "))
+}
+
+pub fn print(args: &[Value], environment: &mut Environment) -> Result<Value, Error> {
+    println!("{}", try!(joined(args, environment)));
+    Ok(Value::Nil)
+}
+
+//Like `print`, but without the newline at the end
+fn write_out(args: &[Value], environment: &mut Environment) -> Result<Value, Error> {
+    let s = try!(joined(args, environment));
+    let mut stdout = io::stdout();
+    match stdout.write_all(s.as_bytes()).and_then(|_| stdout.flush()) {
+        Ok(()) => Ok(Value::Nil),
+        Err(e) => Err(Error::runtime(format!("couldn't write to stdout: {}", e)))
+    }
+}
+
+//Drops the newline from the end of a line, giving nil if there wasn't one to read
+fn line_value(mut line: String, bytes_read: usize) -> Value {
+    if bytes_read == 0 {
+        return Value::Nil;
+    }
+    if line.ends_with("\n") {
+        line.pop();
+        if line.ends_with("\r") {
+            line.pop();
+        }
+    }
+    Value::String(line)
+}
+
+fn read_line() -> Result<Value, Error> {
+    let mut line = String::new();
+    match io::stdin().read_line(&mut line) {
+        Ok(n) => Ok(line_value(line, n)),
+        Err(e) => Err(Error::runtime(format!("couldn't read from stdin: {}", e)))
+    }
+}
 
 #[derive(Debug)]
-pub struct Print;
+enum Handle {
+    Reader(BufReader<File>),
+    Writer(File)
+}
+
+//File opened with `open`. The handle is shared with the method clips it hands out, and is
+//gone once the file's closed.
+#[derive(Debug)]
+pub struct FileClip {
+    path: Rc<String>,
+    handle: Rc<RefCell<Option<Handle>>>
+}
+
+fn read_with<T, F>(path: &str, handle: &RefCell<Option<Handle>>, f: F) -> Result<T, Error>
+    where F: FnOnce(&mut BufReader<File>) -> io::Result<T> {
+    match *handle.borrow_mut() {
+        Some(Handle::Reader(ref mut reader)) => {
+            f(reader).map_err(|e| Error::runtime(format!("couldn't read {}: {}", path, e)))
+        }
+        Some(Handle::Writer(_)) => Err(Error::runtime(format!("{} wasn't opened for reading", path))),
+        None => Err(Error::runtime(format!("{} is closed", path)))
+    }
+}
+
+fn read_file_line(path: &str, handle: &RefCell<Option<Handle>>) -> Result<Value, Error> {
+    let mut line = String::new();
+    let bytes_read = try!(read_with(path, handle, |reader| reader.read_line(&mut line)));
+    Ok(line_value(line, bytes_read))
+}
 
-#[allow(unused_variables, dead_code)]
-impl Clip for Print {
+//Iterator over the lines that are left, which reads them as it goes
+fn lines_value(path: Rc<String>, handle: Rc<RefCell<Option<Handle>>>) -> Value {
+    IterClip::new(Box::new(move |_: &mut Environment| {
+        match try!(read_file_line(&path[..], &handle)) {
+            Value::Nil => Ok(None),
+            line => Ok(Some(line))
+        }
+    })).into_value()
+}
+
+impl FileClip {
+    fn method(&self, name: &str) -> Value {
+        let path = self.path.clone();
+        let handle = self.handle.clone();
+        match name {
+            "read" => native_value(NativeClip::new(name, move |_: Value| {
+                let mut contents = String::new();
+                try!(read_with(&path[..], &handle, |reader| reader.read_to_string(&mut contents)));
+                Ok(contents)
+            })),
+            "read_line" => native_value(NativeClip::new(name, move |_: Value| {
+                read_file_line(&path[..], &handle)
+            })),
+            "lines" => native_value(NativeClip::new(name, move |_: Value| {
+                lines_value(path.clone(), handle.clone())
+            })),
+            //Values are written the same way `print` shows them
+            "write" => native_value(NativeClip::new(name, move |args: &[Value], environment: &mut Environment| {
+                if args.len() != 2 {
+                    return Err(Error::runtime(format!("Wrong number of args for `write`: expected 2 but got {}",
+                                                      args.len())));
+                }
+                let s = try!(display_string(&args[1], environment));
+                match *handle.borrow_mut() {
+                    Some(Handle::Writer(ref mut file)) => {
+                        match file.write_all(s.as_bytes()) {
+                            Ok(()) => Ok(Value::Nil),
+                            Err(e) => Err(Error::runtime(format!("couldn't write to {}: {}", path, e)))
+                        }
+                    }
+                    Some(Handle::Reader(_)) => {
+                        Err(Error::runtime(format!("{} wasn't opened for writing", path)))
+                    }
+                    None => Err(Error::runtime(format!("{} is closed", path)))
+                }
+            })),
+            "close" => native_value(NativeClip::new(name, move |_: Value| {
+                *handle.borrow_mut() = None;
+            })),
+            _ => Value::Nil
+        }
+    }
+}
+
+#[allow(unused_variables)]
+impl Clip for FileClip {
     fn get(&self, key: &Value) -> Value {
-        Value::Nil
+        match *key {
+            Value::String(ref name) => self.method(&name[..]),
+            _ => Value::Nil
+        }
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime("Cannot set a def on a file"))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        false
+    }
+
+    fn play(&mut self, args: Vec<Value>, environment: &mut Environment) -> Result<Value, Error> {
+        Err(Error::runtime("can't play a file"))
+    }
+
+    //Looping over a file goes through its lines
+    fn iter(&self) -> Option<Value> {
+        Some(lines_value(self.path.clone(), self.handle.clone()))
     }
 
-    fn set(&mut self, key: Value, value: Value) -> Result<(), String> {
-        Err("Cannot set a def on print built-in".to_string())
+    fn signature(&self) -> Option<String> {
+        Some(format!("<file {}>", self.path))
     }
+}
 
-    fn play(&mut self, args: Vec<Value>, environment: &mut Environment)
-         -> Result<Value, String> {
-        if args.len() == 1 {
-            println!("{}", args[0]);
-            Ok(Value::Nil)
-        } else {
-            Err("Wrong number of args for `print`".to_string())
+//Mode is "r" to read, "w" to write over the file or "a" to add on to the end of it
+fn open(path: String, mode: String) -> Result<Value, Error> {
+    let mut options = OpenOptions::new();
+    match &mode[..] {
+        "r" => options.read(true),
+        "w" => options.write(true).create(true).truncate(true),
+        "a" => options.append(true).create(true),
+        _ => {return Err(Error::runtime(format!("invalid file mode `{}`, expected r, w or a", mode)));}
+    };
+    let file = match options.open(&path[..]) {
+        Ok(f) => f,
+        Err(e) => {return Err(Error::runtime(format!("couldn't open {}: {}", path, e)));}
+    };
+    let handle = if mode == "r" { Handle::Reader(BufReader::new(file)) } else { Handle::Writer(file) };
+    Ok(Value::Clip(ClipHolder::new(Box::new(FileClip {
+        path: Rc::new(path),
+        handle: Rc::new(RefCell::new(Some(handle)))
+    }))))
+}
+
+fn exists(path: String) -> bool {
+    fs::metadata(&path[..]).is_ok()
+}
+
+//Removes a file, or a directory if it's empty
+fn remove(path: String) -> Result<(), Error> {
+    let result = match fs::metadata(&path[..]) {
+        Ok(ref m) if m.is_dir() => fs::remove_dir(&path[..]),
+        _ => fs::remove_file(&path[..])
+    };
+    result.map_err(|e| Error::runtime(format!("couldn't remove {}: {}", path, e)))
+}
+
+//Names of everything in a directory, sorted
+fn list_dir(path: String) -> Result<Value, Error> {
+    let entries = match fs::read_dir(&path[..]) {
+        Ok(entries) => entries,
+        Err(e) => {return Err(Error::runtime(format!("couldn't list {}: {}", path, e)));}
+    };
+    let mut names = Vec::new();
+    for entry in entries {
+        match entry {
+            Ok(entry) => names.push(entry.file_name().to_string_lossy().into_owned()),
+            Err(e) => {return Err(Error::runtime(format!("couldn't list {}: {}", path, e)));}
         }
     }
+    names.sort();
+    let items = names.into_iter().map(Value::String).collect();
+    Ok(Value::Clip(ClipHolder::new(Box::new(ListClip::new(items)))))
+}
+
+pub fn functions() -> Vec<(&'static str, Value)> {
+    let mut result = Vec::new();
+    {
+        let mut add = |name: &'static str, clip: Box<Clip>| {
+            result.push((name, Value::Clip(ClipHolder::new(clip))));
+        };
+        add("write", Box::new(NativeClip::new("write", write_out)));
+        add("print", Box::new(NativeClip::new("print", print)));
+        add("read_line", Box::new(NativeClip::new("read_line", read_line)));
+        add("open", Box::new(NativeClip::new("open", open)));
+        add("exists", Box::new(NativeClip::new("exists", exists)));
+        add("remove", Box::new(NativeClip::new("remove", remove)));
+        add("list_dir", Box::new(NativeClip::new("list_dir", list_dir)));
+    }
+    result
 }
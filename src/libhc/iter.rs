@@ -0,0 +1,107 @@
+use evaluator::value::Value;
+use evaluator::environment::Environment;
+use evaluator::convert::FromValue;
+use evaluator::iter_clip::{IterClip, LazyIterator, sorted_defs};
+use error::Error;
+
+fn int_arg(name: &str, args: &[Value], idx: usize) -> Result<i64, Error> {
+    match i64::from_value(args[idx].clone()) {
+        Ok(i) => Ok(i),
+        Err(e) => Err(Error::runtime(format!("Bad arg {} for `{}`: {}", idx + 1, name, e.message())))
+    }
+}
+
+//range(stop), range(start, stop) or range(start, stop, step)
+pub fn range(args: &[Value]) -> Result<Value, Error> {
+    let (start, stop, step) = match args.len() {
+        1 => (0, try!(int_arg("range", args, 0)), 1),
+        2 => (try!(int_arg("range", args, 0)), try!(int_arg("range", args, 1)), 1),
+        3 => {
+            (try!(int_arg("range", args, 0)),
+             try!(int_arg("range", args, 1)),
+             try!(int_arg("range", args, 2)))
+        }
+        n => {
+            return Err(Error::runtime(format!("Wrong number of args for `range`: expected 1 to 3 but got {}",
+                                              n)));
+        }
+    };
+    if step == 0 {
+        return Err(Error::runtime("`range` can't have a step of 0"));
+    }
+    let mut cur = start;
+    let iter = IterClip::new(Box::new(move |_: &mut Environment| {
+        if (step > 0 && cur < stop) || (step < 0 && cur > stop) {
+            let result = cur;
+            //Stepping past the largest int means we're past the stop, too
+            cur = cur.checked_add(step).unwrap_or(stop);
+            Ok(Some(Value::Int(result)))
+        } else {
+            Ok(None)
+        }
+    }));
+    Ok(iter.into_value())
+}
+
+pub fn enumerate(iterable: Value) -> Result<Value, Error> {
+    let mut inner = try!(LazyIterator::new(iterable));
+    let mut idx = 0;
+    let iter = IterClip::new(Box::new(move |env: &mut Environment| {
+        match try!(inner.next(env)) {
+            Some(v) => {
+                let result = Value::Tuple(vec![Value::Int(idx), v]);
+                idx += 1;
+                Ok(Some(result))
+            }
+            None => Ok(None)
+        }
+    }));
+    Ok(iter.into_value())
+}
+
+//Stops as soon as any of the iterables runs out
+pub fn zip(args: &[Value]) -> Result<Value, Error> {
+    if args.len() == 0 {
+        return Err(Error::runtime("Wrong number of args for `zip`: expected at least 1 but got 0"));
+    }
+    let mut inners = Vec::with_capacity(args.len());
+    for arg in args.iter() {
+        inners.push(try!(LazyIterator::new(arg.clone())));
+    }
+    let iter = IterClip::new(Box::new(move |env: &mut Environment| {
+        let mut result = Vec::with_capacity(inners.len());
+        for inner in inners.iter_mut() {
+            match try!(inner.next(env)) {
+                Some(v) => result.push(v),
+                None => {return Ok(None);}
+            }
+        }
+        Ok(Some(Value::Tuple(result)))
+    }));
+    Ok(iter.into_value())
+}
+
+fn clip_defs(name: &str, value: Value) -> Result<Vec<(Value, Value)>, Error> {
+    match value {
+        Value::Clip(c) => Ok(sorted_defs(&c)),
+        v => {
+            Err(Error::runtime(format!("Bad arg 1 for `{}`: expected clip but found {}",
+                                       name, v.type_name())))
+        }
+    }
+}
+
+pub fn keys(clip: Value) -> Result<Value, Error> {
+    let defs = try!(clip_defs("keys", clip));
+    Ok(IterClip::from_iter(defs.into_iter().map(|(k, _)| k)).into_value())
+}
+
+pub fn values(clip: Value) -> Result<Value, Error> {
+    let defs = try!(clip_defs("values", clip));
+    Ok(IterClip::from_iter(defs.into_iter().map(|(_, v)| v)).into_value())
+}
+
+pub fn items(clip: Value) -> Result<Value, Error> {
+    let defs = try!(clip_defs("items", clip));
+    Ok(IterClip::from_iter(defs.into_iter().map(|(k, v)| Value::Tuple(vec![k, v]))).into_value())
+}
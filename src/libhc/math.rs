@@ -0,0 +1,163 @@
+use std::f64;
+
+use num::{Signed, FromPrimitive};
+use num::bigint::BigInt;
+
+use evaluator::value::{Value, FloatWrap, big_to_f64};
+use evaluator::clip::ClipHolder;
+use evaluator::native_clip::NativeClip;
+use error::Error;
+
+fn float_value(f: f64) -> Value {
+    Value::Float(FloatWrap::new(f))
+}
+
+fn number_arg(name: &str, idx: usize, value: &Value) -> Result<f64, Error> {
+    match *value {
+        Value::Int(i) => Ok(i as f64),
+        Value::Float(ref f) => Ok(f.get()),
+        Value::BigInt(ref n) => Ok(big_to_f64(n)),
+        ref v => {
+            Err(Error::runtime(format!("Bad arg {} for `{}`: expected number but found {}",
+                                       idx + 1, name, v.type_name())))
+        }
+    }
+}
+
+//Ints stay ints
+fn abs(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::Int(i) => {
+            match i.checked_abs() {
+                Some(result) => Ok(Value::Int(result)),
+                None => Ok(Value::from_big(BigInt::from_i64(i).unwrap().abs()))
+            }
+        }
+        Value::BigInt(n) => Ok(Value::from_big(n.abs())),
+        Value::Float(f) => Ok(float_value(f.get().abs())),
+        v => Err(Error::runtime(format!("Bad arg 1 for `abs`: expected number but found {}", v.type_name())))
+    }
+}
+
+//Smallest or largest of any number of args, keeping whether it was an int or a float
+fn pick(name: &str, args: &[Value], replace: fn(f64, f64) -> bool) -> Result<Value, Error> {
+    if args.len() == 0 {
+        return Err(Error::runtime(format!("Wrong number of args for `{}`: expected at least 1 but got 0",
+                                          name)));
+    }
+    let mut best = &args[0];
+    let mut best_num = try!(number_arg(name, 0, best));
+    for (idx, arg) in args.iter().enumerate().skip(1) {
+        let num = try!(number_arg(name, idx, arg));
+        if replace(num, best_num) {
+            best = arg;
+            best_num = num;
+        }
+    }
+    Ok(best.clone())
+}
+
+fn less(a: f64, b: f64) -> bool {
+    a < b
+}
+
+fn greater(a: f64, b: f64) -> bool {
+    a > b
+}
+
+fn min(args: &[Value]) -> Result<Value, Error> {
+    pick("min", args, less)
+}
+
+fn max(args: &[Value]) -> Result<Value, Error> {
+    pick("max", args, greater)
+}
+
+//Rounding an int gives it back as it is
+fn round_with(name: &str, value: Value, f: fn(f64) -> f64) -> Result<Value, Error> {
+    match value {
+        Value::Int(i) => Ok(Value::Int(i)),
+        Value::BigInt(n) => Ok(Value::BigInt(n)),
+        Value::Float(x) => Ok(float_value(f(x.get()))),
+        v => Err(Error::runtime(format!("Bad arg 1 for `{}`: expected number but found {}", name, v.type_name())))
+    }
+}
+
+fn floor(value: Value) -> Result<Value, Error> {
+    round_with("floor", value, f64::floor)
+}
+
+fn ceil(value: Value) -> Result<Value, Error> {
+    round_with("ceil", value, f64::ceil)
+}
+
+fn round(value: Value) -> Result<Value, Error> {
+    round_with("round", value, f64::round)
+}
+
+//log(x) is the natural log, log(x, base) uses the given base
+fn log(args: &[Value]) -> Result<Value, Error> {
+    match args.len() {
+        1 => Ok(float_value(try!(number_arg("log", 0, &args[0])).ln())),
+        2 => {
+            let x = try!(number_arg("log", 0, &args[0]));
+            let base = try!(number_arg("log", 1, &args[1]));
+            Ok(float_value(x.log(base)))
+        }
+        n => Err(Error::runtime(format!("Wrong number of args for `log`: expected 1 to 2 but got {}", n)))
+    }
+}
+
+//Floats get truncated toward zero, and become big ints if they don't fit in 64 bits
+pub fn int(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::Int(i) => Ok(Value::Int(i)),
+        Value::BigInt(n) => Ok(Value::BigInt(n)),
+        Value::Float(f) => {
+            let x = f.get().trunc();
+            if x.is_nan() || x.is_infinite() {
+                return Err(Error::runtime(format!("can't convert {} to an int", f)));
+            }
+            if x < -9223372036854775808.0 || x >= 9223372036854775808.0 {
+                return Ok(Value::from_big(BigInt::from_f64(x).unwrap()));
+            }
+            Ok(Value::Int(x as i64))
+        }
+        v => Err(Error::runtime(format!("Bad arg 1 for `int`: expected number but found {}", v.type_name())))
+    }
+}
+
+fn float(x: f64) -> f64 {
+    x
+}
+
+pub fn functions() -> Vec<(&'static str, Value)> {
+    let mut result = Vec::new();
+    {
+        let mut add = |name: &'static str, clip: NativeClip| {
+            result.push((name, Value::Clip(ClipHolder::new(Box::new(clip)))));
+        };
+        add("abs", NativeClip::new("abs", abs));
+        add("min", NativeClip::new("min", min));
+        add("max", NativeClip::new("max", max));
+        add("floor", NativeClip::new("floor", floor));
+        add("ceil", NativeClip::new("ceil", ceil));
+        add("round", NativeClip::new("round", round));
+        add("sqrt", NativeClip::new("sqrt", |x: f64| x.sqrt()));
+        add("exp", NativeClip::new("exp", |x: f64| x.exp()));
+        add("log", NativeClip::new("log", log));
+        add("sin", NativeClip::new("sin", |x: f64| x.sin()));
+        add("cos", NativeClip::new("cos", |x: f64| x.cos()));
+        add("tan", NativeClip::new("tan", |x: f64| x.tan()));
+        add("asin", NativeClip::new("asin", |x: f64| x.asin()));
+        add("acos", NativeClip::new("acos", |x: f64| x.acos()));
+        add("atan", NativeClip::new("atan", |x: f64| x.atan()));
+        add("atan2", NativeClip::new("atan2", |y: f64, x: f64| y.atan2(x)));
+        add("int", NativeClip::new("int", int));
+        add("float", NativeClip::new("float", float));
+    }
+    result.push(("pi", float_value(f64::consts::PI)));
+    result.push(("inf", float_value(f64::INFINITY)));
+    result.push(("nan", float_value(f64::NAN)));
+    result
+}
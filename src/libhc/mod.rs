@@ -1,15 +1,58 @@
 mod io;
 mod core;
+mod iter;
+mod math;
+mod types;
+pub mod string;
 
-use libhc::io::{Print};
-use libhc::core::{Import};
-use evaluator::clip::Clip;
+use libhc::core::{Import, PCall};
+use libhc::types::Str;
+use evaluator::clip::{Clip, ClipHolder};
+use evaluator::native_clip::NativeClip;
+use evaluator::standard_clip::StdClip;
+use evaluator::source_map::{SourceFile, SourceMap};
+use evaluator::value::Value;
 use std::collections::HashMap;
+use std::rc::Rc;
+
+//Clip for grouping functions and constants like `string.upper` or `math.pi`
+fn module(name: &str, members: Vec<(&str, Value)>) -> Box<Clip> {
+    let mut defs = HashMap::new();
+    for (key, value) in members.into_iter() {
+        defs.insert(Value::String(key.to_string()), value);
+    }
+    let source_map = SourceMap::new(Rc::new(SourceFile::new(name, "")), Vec::new());
+    Box::new(StdClip::new_with_defs(Vec::new(), Vec::new(), Vec::new(), source_map, defs))
+}
 
 #[allow(dead_code)]
 pub fn open_libs<'a>() -> HashMap<&'static str, Box<Clip>> {
     let mut result = HashMap::new();
-    result.insert("print", Box::new(Print) as Box<Clip>);
+    result.insert("print", Box::new(NativeClip::new("print", io::print)) as Box<Clip>);
     result.insert("import", Box::new(Import) as Box<Clip>);
+    result.insert("pcall", Box::new(PCall) as Box<Clip>);
+    result.insert("error", Box::new(NativeClip::new("error", core::error)) as Box<Clip>);
+    result.insert("clone", Box::new(NativeClip::new("clone", core::clone)) as Box<Clip>);
+    result.insert("deepclone", Box::new(NativeClip::new("deepclone", core::deepclone)) as Box<Clip>);
+    result.insert("extend", Box::new(NativeClip::new("extend", core::extend)) as Box<Clip>);
+    result.insert("range", Box::new(NativeClip::new("range", iter::range)) as Box<Clip>);
+    result.insert("enumerate", Box::new(NativeClip::new("enumerate", iter::enumerate)) as Box<Clip>);
+    result.insert("zip", Box::new(NativeClip::new("zip", iter::zip)) as Box<Clip>);
+    result.insert("keys", Box::new(NativeClip::new("keys", iter::keys)) as Box<Clip>);
+    result.insert("values", Box::new(NativeClip::new("values", iter::values)) as Box<Clip>);
+    result.insert("items", Box::new(NativeClip::new("items", iter::items)) as Box<Clip>);
+    result.insert("type", Box::new(NativeClip::new("type", types::type_of)) as Box<Clip>);
+    result.insert("str", Box::new(Str) as Box<Clip>);
+    result.insert("int", Box::new(NativeClip::new("int", types::int)) as Box<Clip>);
+    result.insert("float", Box::new(NativeClip::new("float", types::float)) as Box<Clip>);
+    result.insert("bool", Box::new(NativeClip::new("bool", types::bool)) as Box<Clip>);
+    result.insert("len", Box::new(NativeClip::new("len", types::len)) as Box<Clip>);
+    result.insert("repr", Box::new(NativeClip::new("repr", types::repr)) as Box<Clip>);
+    let string_fns = string::FUNCTIONS.iter().map(|&name| {
+        (name, Value::Clip(ClipHolder::new(Box::new(string::function(name).unwrap()))))
+    }).collect();
+    result.insert("string", module("string", string_fns));
+    result.insert("math", module("math", math::functions()));
+    result.insert("io", module("io", io::functions()));
     result
 }
@@ -0,0 +1,304 @@
+use evaluator::value::Value;
+use evaluator::clip::ClipHolder;
+use evaluator::convert::FromValue;
+use evaluator::native_clip::NativeClip;
+use evaluator::list_clip::ListClip;
+use evaluator::iter_clip::{IterClip, sorted_defs};
+use evaluator::environment::Environment;
+use evaluator::hooks::display_string;
+use error::Error;
+
+//Names of everything in the `string` clip, which strings also have as methods
+pub static FUNCTIONS: &'static [&'static str] = &[
+    "len", "sub", "find", "replace", "split", "join", "trim", "upper", "lower", "starts_with",
+    "ends_with", "repeat", "chars", "format"
+];
+
+fn arity(name: &str, args: &[Value], min: usize, max: usize) -> Result<(), Error> {
+    if args.len() < min || args.len() > max {
+        return Err(Error::runtime(format!("Wrong number of args for `{}`: expected {} to {} but got {}",
+                                          name, min, max, args.len())));
+    }
+    Ok(())
+}
+
+//Gets an arg as a T, or None if it's left off
+fn arg<T: FromValue>(name: &str, args: &[Value], idx: usize) -> Result<Option<T>, Error> {
+    match args.get(idx) {
+        Some(v) => {
+            match T::from_value(v.clone()) {
+                Ok(result) => Ok(Some(result)),
+                Err(e) => Err(Error::runtime(format!("Bad arg {} for `{}`: {}", idx + 1, name, e.message())))
+            }
+        }
+        None => Ok(None)
+    }
+}
+
+//Char position for an index that may count back from the end, clamped to the string
+fn char_pos(idx: i64, len: usize) -> usize {
+    let real_idx = if idx < 0 { idx + len as i64 } else { idx };
+    if real_idx < 0 {
+        0
+    } else if real_idx as usize > len {
+        len
+    } else {
+        real_idx as usize
+    }
+}
+
+//Char at an index, or nil if it's out of range. Negative indices count back from the end.
+pub fn char_at(s: &str, idx: i64) -> Value {
+    let len = s.chars().count() as i64;
+    let real_idx = if idx < 0 { idx + len } else { idx };
+    if real_idx < 0 || real_idx >= len {
+        return Value::Nil;
+    }
+    match s.chars().nth(real_idx as usize) {
+        Some(c) => Value::String(c.to_string()),
+        None => Value::Nil
+    }
+}
+
+fn len(s: String) -> i64 {
+    s.chars().count() as i64
+}
+
+//sub(s, start) or sub(s, start, end), with the end left out of the result
+fn sub(args: &[Value]) -> Result<Value, Error> {
+    try!(arity("sub", args, 2, 3));
+    let s: String = try!(arg("sub", args, 0)).unwrap();
+    let len = s.chars().count();
+    let start = char_pos(try!(arg("sub", args, 1)).unwrap(), len);
+    let end = match try!(arg::<Option<i64>>("sub", args, 2)) {
+        Some(Some(end)) => char_pos(end, len),
+        _ => len
+    };
+    if end <= start {
+        return Ok(Value::String(String::new()));
+    }
+    Ok(Value::String(s.chars().skip(start).take(end - start).collect()))
+}
+
+//Char index of the first match at or after `start`, or nil if there isn't one
+fn find(args: &[Value]) -> Result<Value, Error> {
+    try!(arity("find", args, 2, 3));
+    let s: String = try!(arg("find", args, 0)).unwrap();
+    let needle: String = try!(arg("find", args, 1)).unwrap();
+    let start = match try!(arg::<Option<i64>>("find", args, 2)) {
+        Some(Some(start)) => char_pos(start, s.chars().count()),
+        _ => 0
+    };
+    let byte_start = s.char_indices().nth(start).map(|(i, _)| i).unwrap_or(s.len());
+    match s[byte_start..].find(&needle[..]) {
+        Some(byte_idx) => {
+            let skipped = s[byte_start..byte_start + byte_idx].chars().count();
+            Ok(Value::Int((start + skipped) as i64))
+        }
+        None => Ok(Value::Nil)
+    }
+}
+
+fn replace(s: String, from: String, to: String) -> Result<String, Error> {
+    if from.is_empty() {
+        return Err(Error::runtime("can't replace an empty string"));
+    }
+    Ok(s.replace(&from[..], &to[..]))
+}
+
+fn list_value(items: Vec<Value>) -> Value {
+    Value::Clip(ClipHolder::new(Box::new(ListClip::new(items))))
+}
+
+//Splits on whitespace if there's no separator
+fn split(args: &[Value]) -> Result<Value, Error> {
+    try!(arity("split", args, 1, 2));
+    let s: String = try!(arg("split", args, 0)).unwrap();
+    let parts: Vec<Value> = match try!(arg::<Option<String>>("split", args, 1)) {
+        Some(Some(sep)) => {
+            if sep.is_empty() {
+                return Err(Error::runtime("can't split on an empty separator"));
+            }
+            s.split(&sep[..]).map(|p| Value::String(p.to_string())).collect()
+        }
+        _ => s.split_whitespace().map(|p| Value::String(p.to_string())).collect()
+    };
+    Ok(list_value(parts))
+}
+
+//join(sep, items) puts the separator between the items of a tuple or list, shown the same way
+//`print` shows them
+fn join(args: &[Value], environment: &mut Environment) -> Result<Value, Error> {
+    try!(arity("join", args, 2, 2));
+    let sep: String = try!(arg("join", args, 0)).unwrap();
+    let values = match args[1] {
+        Value::Tuple(ref vals) => vals.clone(),
+        Value::Clip(ref c) => sorted_defs(c).into_iter().map(|(_, v)| v).collect(),
+        ref v => {
+            return Err(Error::runtime(format!("Bad arg 2 for `join`: expected tuple or list but found {}",
+                                              v.type_name())));
+        }
+    };
+    let mut strings = Vec::with_capacity(values.len());
+    for v in values.iter() {
+        strings.push(try!(display_string(v, environment)));
+    }
+    Ok(Value::String(strings.join(&sep[..])))
+}
+
+fn trim(s: String) -> String {
+    s.trim().to_string()
+}
+
+fn upper(s: String) -> String {
+    s.to_uppercase()
+}
+
+fn lower(s: String) -> String {
+    s.to_lowercase()
+}
+
+fn starts_with(s: String, prefix: String) -> bool {
+    s.starts_with(&prefix[..])
+}
+
+fn ends_with(s: String, suffix: String) -> bool {
+    s.ends_with(&suffix[..])
+}
+
+//Longest string in bytes that `repeat` will make
+const MAX_REPEAT_LEN: usize = 1 << 30;
+
+fn repeat(s: String, count: i64) -> Result<String, Error> {
+    if count < 0 {
+        return Err(Error::runtime("can't repeat a string a negative number of times"));
+    }
+    let len = match s.len().checked_mul(count as usize) {
+        Some(len) if len <= MAX_REPEAT_LEN => len,
+        _ => {return Err(Error::runtime(format!("repeating a string {} times makes it too long", count)));}
+    };
+    if len == 0 {
+        return Ok(String::new());
+    }
+    let mut result = String::with_capacity(len);
+    for _ in 0..count {
+        result.push_str(&s[..]);
+    }
+    Ok(result)
+}
+
+fn chars(s: String) -> Value {
+    let chars: Vec<Value> = s.chars().map(|c| Value::String(c.to_string())).collect();
+    IterClip::from_iter(chars.into_iter()).into_value()
+}
+
+//Parts of a format spec like `>8.2`: alignment, zero padding, width and precision
+struct FormatSpec {
+    align: Option<char>,
+    zero: bool,
+    width: usize,
+    precision: Option<usize>
+}
+
+fn parse_spec(spec: &str) -> Option<FormatSpec> {
+    let mut rest = spec;
+    let align = match rest.chars().next() {
+        Some(c) if c == '<' || c == '>' || c == '^' => {
+            rest = &rest[1..];
+            Some(c)
+        }
+        _ => None
+    };
+    let zero = rest.starts_with("0");
+    if zero {
+        rest = &rest[1..];
+    }
+    let (width_str, precision_str) = match rest.find('.') {
+        Some(idx) => (&rest[..idx], Some(&rest[idx + 1..])),
+        None => (rest, None)
+    };
+    let width = if width_str.is_empty() { 0 } else { match width_str.parse() {
+        Ok(w) => w,
+        Err(_) => {return None;}
+    }};
+    let precision = match precision_str {
+        Some(p) => match p.parse() {
+            Ok(p) => Some(p),
+            Err(_) => {return None;}
+        },
+        None => None
+    };
+    Some(FormatSpec{align: align, zero: zero, width: width, precision: precision})
+}
+
+//format(value, spec) pads the value to a width in chars, and rounds numbers or cuts strings to
+//the precision, e.g. format(3.14159, ">8.2") gives "    3.14"
+fn format(value: Value, spec: String) -> Result<String, Error> {
+    let spec = match parse_spec(&spec[..]) {
+        Some(s) => s,
+        None => {return Err(Error::runtime(format!("invalid format spec `{}`", spec)));}
+    };
+    let body = match (value, spec.precision) {
+        (Value::Float(f), Some(p)) => format!("{:.*}", p, f.get()),
+        (Value::Int(i), Some(p)) => format!("{:.*}", p, i as f64),
+        (Value::String(s), Some(p)) => s.chars().take(p).collect(),
+        (v, _) => v.to_string()
+    };
+    let len = body.chars().count();
+    if len >= spec.width {
+        return Ok(body);
+    }
+    let padding = spec.width - len;
+    if spec.zero && spec.align.is_none() {
+        //Zeros go after the sign
+        let (sign, digits) = if body.starts_with("-") { body.split_at(1) } else { ("", &body[..]) };
+        return Ok(format!("{}{}{}", sign, repeat_char('0', padding), digits));
+    }
+    Ok(match spec.align.unwrap_or('<') {
+        '>' => format!("{}{}", repeat_char(' ', padding), body),
+        '^' => {
+            let left = padding / 2;
+            format!("{}{}{}", repeat_char(' ', left), body, repeat_char(' ', padding - left))
+        }
+        _ => format!("{}{}", body, repeat_char(' ', padding))
+    })
+}
+
+fn repeat_char(c: char, count: usize) -> String {
+    (0..count).map(|_| c).collect()
+}
+
+pub fn function(name: &str) -> Option<NativeClip> {
+    Some(match name {
+        "len" => NativeClip::new(name, len),
+        "sub" => NativeClip::new(name, sub),
+        "find" => NativeClip::new(name, find),
+        "replace" => NativeClip::new(name, replace),
+        "split" => NativeClip::new(name, split),
+        "join" => NativeClip::new(name, join),
+        "trim" => NativeClip::new(name, trim),
+        "upper" => NativeClip::new(name, upper),
+        "lower" => NativeClip::new(name, lower),
+        "starts_with" => NativeClip::new(name, starts_with),
+        "ends_with" => NativeClip::new(name, ends_with),
+        "repeat" => NativeClip::new(name, repeat),
+        "chars" => NativeClip::new(name, chars),
+        "format" => NativeClip::new(name, format),
+        _ => {return None;}
+    })
+}
+
+//What `s.key` and `s[key]` give for a string: the char at an int index, or a string function
+//to play as a method with `|`
+pub fn get(s: &str, key: &Value) -> Value {
+    match *key {
+        Value::Int(idx) => char_at(s, idx),
+        Value::String(ref name) => {
+            match function(&name[..]) {
+                Some(f) => Value::Clip(ClipHolder::new(Box::new(f))),
+                None => Value::Nil
+            }
+        }
+        _ => Value::Nil
+    }
+}
@@ -0,0 +1,107 @@
+use std::str::FromStr;
+
+use num::bigint::BigInt;
+
+use evaluator::value::{Value, FloatWrap};
+use evaluator::clip::Clip;
+use evaluator::convert::FromValue;
+use evaluator::environment::Environment;
+use evaluator::hooks::{display_string, repr_string};
+use libhc::math;
+use error::Error;
+
+#[derive(Debug)]
+pub struct Str;
+
+//str(v) gives the same string that printing v does, so `__str` hooks get played
+#[allow(unused_variables, dead_code)]
+impl Clip for Str {
+    fn get(&self, key: &Value) -> Value {
+        Value::Nil
+    }
+
+    fn set(&mut self, key: Value, value: Value) -> Result<(), Error> {
+        Err(Error::runtime("Cannot set a def on str built-in"))
+    }
+
+    fn contains(&self, key: &Value) -> bool {
+        false
+    }
+
+    fn signature(&self) -> Option<String> {
+        Some("<native fn str>".to_string())
+    }
+
+    fn play(&mut self, args: Vec<Value>, environment: &mut Environment)
+         -> Result<Value, Error> {
+        if args.len() == 1 {
+            Ok(Value::String(try!(display_string(&args[0], environment))))
+        } else {
+            Err(Error::runtime(format!("Wrong number of args for `str`: expected 1 but got {}", args.len())))
+        }
+    }
+}
+
+pub fn type_of(value: Value) -> String {
+    value.type_name().to_string()
+}
+
+//Strings that aren't ints give back nil, and anything else is truncated like `math.int`
+pub fn int(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::String(s) => {
+            let s = s.trim();
+            match i64::from_str(s) {
+                Ok(i) => Ok(Value::Int(i)),
+                Err(_) => {
+                    match BigInt::from_str(s) {
+                        Ok(n) => Ok(Value::from_big(n)),
+                        Err(_) => Ok(Value::Nil)
+                    }
+                }
+            }
+        }
+        v => math::int(v)
+    }
+}
+
+//Strings that aren't numbers give back nil
+pub fn float(value: Value) -> Result<Value, Error> {
+    match value {
+        Value::String(s) => {
+            match f64::from_str(s.trim()) {
+                Ok(f) => Ok(Value::Float(FloatWrap::new(f))),
+                Err(_) => Ok(Value::Nil)
+            }
+        }
+        v => {
+            match f64::from_value(v) {
+                Ok(f) => Ok(Value::Float(FloatWrap::new(f))),
+                Err(e) => Err(Error::runtime(format!("Bad arg 1 for `float`: {}", e.message())))
+            }
+        }
+    }
+}
+
+//Only nil and false are false
+pub fn bool(value: Value) -> bool {
+    match value {
+        Value::Nil | Value::Bool(false) => false,
+        _ => true
+    }
+}
+
+//Chars in a string, items in a tuple or list, or defs in any other clip
+pub fn len(value: Value) -> Result<i64, Error> {
+    match value {
+        Value::String(s) => Ok(s.chars().count() as i64),
+        Value::Tuple(vals) => Ok(vals.len() as i64),
+        Value::Clip(c) => Ok(c.borrow().keys().len() as i64),
+        v => Err(Error::runtime(format!("Bad arg 1 for `len`: expected string, tuple or clip but found {}",
+                                        v.type_name())))
+    }
+}
+
+pub fn repr(value: Value) -> String {
+    repr_string(&value)
+}
@@ -1,12 +1,56 @@
 extern crate housecat;
 use std::env;
+use std::io;
+use std::io::prelude::*;
 
-use housecat::Interpreter;
+use housecat::{Interpreter, Error};
+
+//Input is incomplete if parsing ran into the end of it, e.g. an unclosed `{` or `do ... end` block
+fn is_incomplete(err: &Error, input: &str) -> bool {
+    match *err {
+        Error::Parse{ref span, ..} => span.line >= input.lines().count(),
+        _ => false
+    }
+}
+
+fn run_repl() {
+    let mut interpreter = Interpreter::new();
+    let stdin = io::stdin();
+    let mut input = String::new();
+    loop {
+        if input.is_empty() {
+            print!("> ");
+        } else {
+            print!(". ");
+        }
+        io::stdout().flush().unwrap();
+        let mut line = String::new();
+        match stdin.read_line(&mut line) {
+            Ok(0) => {
+                println!("");
+                break;
+            }
+            Ok(_) => (),
+            Err(e) => {
+                println!("couldn't read input: {}", e);
+                break;
+            }
+        }
+        input.push_str(&line);
+        match interpreter.eval_line(&input) {
+            Ok(Some(v)) => println!("{}", v),
+            Ok(None) => (),
+            Err(ref e) if is_incomplete(e, &input) => continue,
+            Err(e) => println!("{}", e)
+        }
+        input.clear();
+    }
+}
 
 fn main() {
     let command_args: Vec<String> = env::args().collect();
     if command_args.len() <= 1 {
-        println!("No .hcat file provided!");
+        run_repl();
     } else {
         let mut interpreter = Interpreter::new();
         match interpreter.interpret_file(&command_args[1][..]) {
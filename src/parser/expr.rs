@@ -8,21 +8,31 @@ use parser::{ParseResult, ParserError, ParserErrorType};
 fn parse_primary_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
     match tokens {
         // <ident>
-        [Tok{token: Token::Ident(id), line, ..}, rest..] => {
-            Ok((Expr{expr: ExprType::Ident{name: id}, data: AstData{line: line}}, rest))
+        [Tok{token: Token::Ident(id), line, col, ..}, rest..] => {
+            Ok((Expr{expr: ExprType::Ident{name: id}, data: AstData{line: line, col: col}}, rest))
         }
         // "(" <expr> ...
-        [Tok{token: Token::OpenParen, line, ..}, rest..] => {
+        [Tok{token: Token::OpenParen, line, col, ..}, rest..] => {
             let (parsed_list, tokens_after_list) = try!(parse_expr_list(rest, Token::CloseParen));
             // if parsed_list.len() == 1 {
             //     Ok((parsed_list.remove(0), rest))
             // } else {
-                Ok((Expr{expr: ExprType::Tuple{values: parsed_list}, data: AstData{line: line}},
+                Ok((Expr{expr: ExprType::Tuple{values: parsed_list}, data: AstData{line: line, col: col}},
                     tokens_after_list))
             //}
         },
+        // "[" <expr-list-const>
+        [Tok{token: Token::OpenBrac, line, col, ..}, rest..] => {
+            let (parsed_list, tokens_after_list) = match rest {
+                // ... "]"
+                [Tok{token: Token::CloseBrac, ..}, rest..] => (vec![], rest),
+                _ => try!(parse_expr_list(rest, Token::CloseBrac))
+            };
+            Ok((Expr{expr: ExprType::List{values: parsed_list}, data: AstData{line: line, col: col}},
+                tokens_after_list))
+        }
         // "{" <clip-statements>
-        [Tok{token: Token::OpenCurly, line, ..}, rest..] => {
+        [Tok{token: Token::OpenCurly, line, col, ..}, rest..] => {
             let (parsed_list, tokens_after_list) = try!(parse_clip_statements(rest));
             Ok((
                 Expr{
@@ -33,11 +43,11 @@ fn parse_primary_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
                             statements:parsed_list
                         }
                     },
-                    data:AstData{line: line}
+                    data:AstData{line: line, col: col}
                 }, tokens_after_list))
         }
         // "fn" <clip-def>
-        [Tok{token: Token::Fn, line, ..}, rest..] => {
+        [Tok{token: Token::Fn, line, col, ..}, rest..] => {
             let ((parsed_params, parsed_returns, parsed_statements), tokens_after_list) = {
                 try!(parse_clip_def(rest))
             };
@@ -50,33 +60,43 @@ fn parse_primary_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
                             statements: parsed_statements
                         },
                     },
-                    data: AstData{line: line}
+                    data: AstData{line: line, col: col}
                 }, tokens_after_list))
         }
         // <bool>
-        [Tok{token: Token::Bool(b), line, ..}, rest..] => {
+        [Tok{token: Token::Bool(b), line, col, ..}, rest..] => {
             Ok((Expr{expr: ExprType::Literal{value: Literal::Bool(b)},
-                     data: AstData{line: line}}, rest))
+                     data: AstData{line: line, col: col}}, rest))
         }
         // <int>
-        [Tok{token: Token::Int(i), line, ..}, rest..] => {
+        [Tok{token: Token::Int(i), line, col, ..}, rest..] => {
             Ok((Expr{expr: ExprType::Literal{value: Literal::Int(i)},
-                     data: AstData{line: line}}, rest))
+                     data: AstData{line: line, col: col}}, rest))
+        }
+        [Tok{token: Token::BigInt(ref n), line, col, ..}, rest..] => {
+            Ok((Expr{expr: ExprType::Literal{value: Literal::BigInt(n)},
+                     data: AstData{line: line, col: col}}, rest))
         }
         // <float>
-        [Tok{token: Token::Float(f), line, ..}, rest..] => {
+        [Tok{token: Token::Float(f), line, col, ..}, rest..] => {
             Ok((Expr{expr: ExprType::Literal{value: Literal::Float(f)},
-                     data: AstData{line: line}}, rest))
+                     data: AstData{line: line, col: col}}, rest))
         }
         // <string>
-        [Tok{token: Token::String(ref s), line, ..}, rest..] => {
+        [Tok{token: Token::String(ref s), line, col, ..}, rest..] => {
             Ok((Expr{expr: ExprType::Literal{value: Literal::String(&s[..])},
-                     data: AstData{line: line}}, rest))
+                     data: AstData{line: line, col: col}}, rest))
+        }
+        // <interpolated-string>
+        [Tok{token: Token::InterpStart, line, col, ..}, rest..] => {
+            let (parsed_parts, tokens_after_parts) = try!(parse_interp_parts(rest));
+            Ok((Expr{expr: ExprType::Interpolate{parts: parsed_parts},
+                     data: AstData{line: line, col: col}}, tokens_after_parts))
         }
         // "nil"
-        [Tok{token: Token::Nil, line, ..}, rest..] => {
+        [Tok{token: Token::Nil, line, col, ..}, rest..] => {
             Ok((Expr{expr: ExprType::Literal{value: Literal::Nil},
-                     data: AstData{line: line}}, rest))
+                     data: AstData{line: line, col: col}}, rest))
         }
         [ref tok, ..] => Err(ParserError{
             actual: tok.clone(),
@@ -87,6 +107,51 @@ fn parse_primary_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
     }
 }
 
+// <interp-parts>
+fn parse_interp_parts<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Vec<Expr<'a>>> {
+    let mut parts = vec![];
+    let mut my_toks = tokens;
+    loop {
+        match my_toks {
+            // <string> <interp-parts>
+            [Tok{token: Token::String(ref s), line, col, ..}, rest..] => {
+                parts.push(Expr{expr: ExprType::Literal{value: Literal::String(&s[..])},
+                                data: AstData{line: line, col: col}});
+                my_toks = rest;
+            }
+            // "{" <expr> "}" <interp-parts>
+            [ref open_tok, rest..] if open_tok.token == Token::OpenCurly => {
+                let (parsed_expr, tokens_after_expr) = try!(parse_expr(rest));
+                match tokens_after_expr {
+                    [Tok{token: Token::CloseCurly, ..}, rest..] => {
+                        parts.push(parsed_expr);
+                        my_toks = rest;
+                    }
+                    [ref tok, ..] => {return Err(ParserError{
+                        actual: tok.clone(),
+                        error_type: ParserErrorType::ExpectedMatchingToken{
+                            expected: Token::CloseCurly,
+                            start_tok: open_tok.clone()
+                        },
+                        hint: Some("only one expression can go inside each `{}` of a string")
+                    });},
+                    [] => panic!("Missing EOF")
+                }
+            }
+            // <interp-end>
+            [Tok{token: Token::InterpEnd, ..}, rest..] => {return Ok((parts, rest));}
+            [ref tok, ..] => {return Err(ParserError{
+                actual: tok.clone(),
+                error_type: ParserErrorType::ExpectedTokens{
+                    expected: vec!(Token::InterpEnd),
+                },
+                hint: None
+            });},
+            [] => panic!("Missing EOF")
+        }
+    }
+}
+
 // <postfix-expr>
 fn parse_postfix_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
     // <primary-expr> ...
@@ -101,7 +166,7 @@ fn parse_postfix_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
                     };
                     Ok((Expr{expr: ExprType::Postfix{expr: Box::new(parsed_expr),
                                                postfixes: parsed_postfixes},
-                             data: AstData{line: first_tok.line}}, tokens_after_postfix))
+                             data: AstData{line: first_tok.line, col: first_tok.col}}, tokens_after_postfix))
                 },
                 _ => Ok((parsed_expr, tokens_after_expr))
             }
@@ -235,7 +300,7 @@ fn parse_expr_list<'a>(tokens: &'a[Tok], delimiter_tok: Token<'a>) -> ParseResul
                 [ref tok, ..] => Err(ParserError{
                     actual: tok.clone(),
                     error_type: ParserErrorType::ExpectedTokens{
-                        expected: vec!(Token::CloseParen, Token::Comma)
+                        expected: vec!(delimiter_tok, Token::Comma)
                     },
                     hint: None
                 }),
@@ -250,24 +315,24 @@ fn parse_expr_list<'a>(tokens: &'a[Tok], delimiter_tok: Token<'a>) -> ParseResul
 fn parse_unary_expr<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Expr<'a>> {
     match tokens {
         // "-" ...
-        [Tok{token: Token::Sub, line, ..}, rest..] => {
+        [Tok{token: Token::Sub, line, col, ..}, rest..] => {
             let (parsed_expr, tokens_after_expr) = try!(parse_unary_expr(rest));
             Ok((Expr{expr: ExprType::UnOp{op: UnOp::Neg, expr: Box::new(parsed_expr)},
-                     data: AstData{line: line}},
+                     data: AstData{line: line, col: col}},
                 tokens_after_expr))
         }
         // "!" ...
-        [Tok{token: Token::Not, line, ..}, rest..] => {
+        [Tok{token: Token::Not, line, col, ..}, rest..] => {
             let (parsed_expr, tokens_after_expr) = try!(parse_unary_expr(rest));
             Ok((Expr{expr: ExprType::UnOp{op: UnOp::Not, expr: Box::new(parsed_expr)},
-                     data: AstData{line: line}},
+                     data: AstData{line: line, col: col}},
                 tokens_after_expr))
         }
         // "$" ...
-        [Tok{token: Token::Get, line, ..}, rest..] => {
+        [Tok{token: Token::Get, line, col, ..}, rest..] => {
             let (parsed_expr, tokens_after_expr) = try!(parse_unary_expr(rest));
             Ok((Expr{expr: ExprType::UnOp{op: UnOp::Get, expr: Box::new(parsed_expr)},
-                     data: AstData{line: line}},
+                     data: AstData{line: line, col: col}},
                 tokens_after_expr))
         }
         // <postfix-expr>
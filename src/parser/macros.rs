@@ -6,7 +6,7 @@ macro_rules! parse_expr_binary_op(
         match tokens_after_lhs {
             $(
                 // ... <op> <RHS>
-                [Tok{token: $tok, ..}, rest..] => {
+                [Tok{token: $tok, line, col, ..}, rest..] => {
                     let (parsed_rhs, tokens_after_term) = try!($parse_rhs(rest));
                     Ok((
                         Expr{
@@ -15,7 +15,7 @@ macro_rules! parse_expr_binary_op(
                                 lhs: Box::new(parsed_lhs),
                                 rhs: Box::new(parsed_rhs),
                             },
-                            data: AstData{line: $tokens[0].line}
+                            data: AstData{line: line, col: col}
                         },
                         tokens_after_term
                     ))
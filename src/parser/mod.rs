@@ -7,8 +7,7 @@ mod clip;
 use token::{Tok, Token};
 use ast::*;
 use parser::stmt::parse_base_statements;
-use std::fmt;
-use utils::get_caret_string;
+use error::{Error, Span};
 
 enum ParserErrorType<'a> {
     ExpectedTokens {
@@ -30,8 +29,8 @@ pub struct ParserError<'a> {
 }
 
 impl<'a> ParserError<'a> {
-    fn error_string(&self) -> String {
-        let err = match self.error_type {
+    fn message(&self) -> String {
+        match self.error_type {
             ParserErrorType::ExpectedTokens {ref expected} => {
                 let len = expected.len();
                 let mut sum_string = "".to_string();
@@ -64,24 +63,15 @@ impl<'a> ParserError<'a> {
             ParserErrorType::ExpectedBaseExpression => {
                 format!("expected an ident, literal, or expression but found `{}`", self.actual.token)
             }
-        };
-        format!("PARSING FAILURE at {}:{} {}",
-                self.actual.line + 1,
-                self.actual.col + 1,
-                err)
+        }
     }
-}
 
-impl<'a> fmt::Display for ParserError<'a> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        try!(write!(f, "{}", self.error_string()));
-        let line_as_string = (self.actual.line + 1).to_string();
-        try!(write!(f, "\n{}: {}", line_as_string, self.actual.line_string));
-        let ret = write!(f, "\n{}", get_caret_string(self.actual.col + line_as_string.len() + 2));
-        if let Some(hint) = self.hint {
-            return write!(f, "\n\nHint: {}\n", hint);
+    fn into_error(self) -> Error {
+        Error::Parse {
+            span: Span::new("", self.actual.line, self.actual.col, self.actual.line_string),
+            message: self.message(),
+            hint: self.hint.map(|h| h.to_string())
         }
-        return ret;
     }
 }
 
@@ -97,10 +87,10 @@ fn print_toks<'a>(func: &str, tokens: &'a[Tok]) {
 }
 
 pub fn parse_tokens<'a>(tokens: &'a[Tok], cur_statements: &'a mut Vec<Stmt<'a>>)
-                        -> Result<&'a Vec<Stmt<'a>>, String> {
+                        -> Result<&'a Vec<Stmt<'a>>, Error> {
     match parse_base_statements(tokens, cur_statements) {
         Ok((v, _)) => Ok(v),
-        Err(s) => Err(s.to_string())
+        Err(e) => Err(e.into_error())
     }
 }
 
@@ -109,9 +99,14 @@ mod test {
     use super::{ParserError, ParserErrorType};
     use token::{Token, Tok};
 
+    //First line of the error as it's shown, without the source line under it
+    fn error_line(e: ParserError) -> String {
+        e.into_error().to_string().lines().next().unwrap().to_string()
+    }
+
     #[test]
     fn test_display_expected_tokens_error() {
-        let example = ParserError {
+        let example = error_line(ParserError {
             actual: Tok{
                 token: Token::Ident("bagelman"),
                 line: 0,
@@ -123,9 +118,9 @@ mod test {
                 expected: vec!(Token::Sub),
             },
             hint: None,
-        }.error_string();
+        });
         assert_eq!(example, "PARSING FAILURE at 1:10 expected `-` but found `bagelman`".to_string());
-        let example = ParserError {
+        let example = error_line(ParserError {
             actual: Tok{
                 token: Token::Ident("bagelman"),
                 line: 0,
@@ -137,9 +132,9 @@ mod test {
                 expected: vec!(Token::Sub, Token::Add)
             },
             hint: None,
-        }.error_string();
+        });
         assert_eq!(example, "PARSING FAILURE at 1:10 expected `-` or `+` but found `bagelman`".to_string());
-        let example = ParserError {
+        let example = error_line(ParserError {
             actual: Tok{
                 token: Token::Ident("bagelman"),
                 line: 0,
@@ -151,13 +146,13 @@ mod test {
                 expected: vec!(Token::Sub, Token::Add, Token::Mul),
             },
             hint: None,
-        }.error_string();
+        });
         assert_eq!(example, "PARSING FAILURE at 1:10 expected `-`, `+`, or `*` but found `bagelman`".to_string());
     }
 
     #[test]
     fn test_display_expected_matching_token_error() {
-        let example = ParserError {
+        let example = error_line(ParserError {
             actual: Tok{
                 token: Token::Ident("bagelman"),
                 line: 2,
@@ -176,7 +171,7 @@ mod test {
                 }
             },
             hint: None,
-        }.error_string();
+        });
         assert_eq!(example, "PARSING FAILURE at 3:10 must match `[` at 2:11 with `]` but found `bagelman`".to_string());
     }
 }
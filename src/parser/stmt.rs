@@ -65,21 +65,21 @@ fn parse_stmt_items<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Stmt<'a>> {
     let (parsed_items, tokens_after_items) = try!(parse_item_list(tokens));
     match tokens_after_items {
         // ... ":" <expr>
-        [Tok{token: Token::Def, line, ..}, rest..] => {
+        [Tok{token: Token::Def, line, col, ..}, rest..] => {
             let (parsed_expr, tokens_after_expr) = try!(parse_expr(rest));
             Ok((Stmt{stmt: StmtType::Def{items: parsed_items, expr: Box::new(parsed_expr)},
-                     data: AstData{line: line}}, tokens_after_expr))
+                     data: AstData{line: line, col: col}}, tokens_after_expr))
         }
         // ... "=" <expr>
-        [Tok{token: Token::Assign, line, ..}, rest..] => {
+        [Tok{token: Token::Assign, line, col, ..}, rest..] => {
             let (parsed_expr, tokens_after_expr) = try!(parse_expr(rest));
             Ok((Stmt{stmt: StmtType::Assign{items: parsed_items, expr: Box::new(parsed_expr)},
-                     data: AstData{line: line}}, tokens_after_expr))
+                     data: AstData{line: line, col: col}}, tokens_after_expr))
         }
         // EPS
-        [Tok{line, ..}, ..] => {
+        [Tok{line, col, ..}, ..] => {
             Ok((Stmt{stmt: StmtType::Bare{items: parsed_items},
-                     data: AstData{line: line}}, tokens_after_items))
+                     data: AstData{line: line, col: col}}, tokens_after_items))
         }
         [] => panic!("Missing EOF")
     }
@@ -99,7 +99,7 @@ fn parse_stmt<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Stmt<'a>> {
                                 try!(parse_if_statements(rest, parsed_expr))
                             };
                             Ok((Stmt{stmt: StmtType::If{clauses: clauses},
-                                     data: AstData{line: start_tok.line}}, tokens_after_if))
+                                     data: AstData{line: start_tok.line, col: start_tok.col}}, tokens_after_if))
                         }
                         [ref tok, ..] => Err(ParserError{
                             actual: tok.clone(),
@@ -121,7 +121,7 @@ fn parse_stmt<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Stmt<'a>> {
                             let (stmt_list, tokens_after_list) = try!(parse_block_statements(rest));
                             Ok((Stmt{stmt: StmtType::While{condition: Box::new(parsed_expr),
                                                            statements: stmt_list},
-                                     data: AstData{line: start_tok.line}}, tokens_after_list))
+                                     data: AstData{line: start_tok.line, col: start_tok.col}}, tokens_after_list))
                         }
                         [ref tok, ..] => Err(ParserError{
                             actual: tok.clone(),
@@ -147,7 +147,7 @@ fn parse_stmt<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Stmt<'a>> {
                                     Ok((Stmt{stmt: StmtType::For{idents: parsed_rets,
                                                                  iterator: Box::new(parsed_expr),
                                                                  statements: stmt_list},
-                                             data: AstData{line: start_tok.line}}, tokens_after_list))
+                                             data: AstData{line: start_tok.line, col: start_tok.col}}, tokens_after_list))
                                 }
                                 [ref tok, ..] => Err(ParserError{
                                     actual: tok.clone(),
@@ -173,9 +173,36 @@ fn parse_stmt<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Stmt<'a>> {
                         [] => panic!("Missing EOF")
                     }
                 }
+                // "try" <try-statements> <ident> <block-statements>
+                Token::Try => {
+                    let (stmt_list, tokens_after_list) = try!(parse_try_statements(rest));
+                    match tokens_after_list {
+                        [Tok{token: Token::Ident(ident), ..}, rest..] => {
+                            let (catch_list, tokens_after_catch) = try!(parse_block_statements(rest));
+                            Ok((Stmt{stmt: StmtType::Try{statements: stmt_list,
+                                                         ident: ident,
+                                                         catch_statements: catch_list},
+                                     data: AstData{line: start_tok.line, col: start_tok.col}}, tokens_after_catch))
+                        }
+                        [ref tok, ..] => Err(ParserError{
+                            actual: tok.clone(),
+                            error_type: ParserErrorType::ExpectedIdent,
+                            hint: Some("`catch` must be followed by a name for the error")
+                        }),
+                        [] => panic!("Missing EOF")
+                    }
+                }
                 // "return"
                 Token::Return => {
-                    Ok((Stmt{stmt: StmtType::Return, data: AstData{line: start_tok.line}}, rest))
+                    Ok((Stmt{stmt: StmtType::Return, data: AstData{line: start_tok.line, col: start_tok.col}}, rest))
+                }
+                // "break"
+                Token::Break => {
+                    Ok((Stmt{stmt: StmtType::Break, data: AstData{line: start_tok.line, col: start_tok.col}}, rest))
+                }
+                // "continue"
+                Token::Continue => {
+                    Ok((Stmt{stmt: StmtType::Continue, data: AstData{line: start_tok.line, col: start_tok.col}}, rest))
                 }
                 // <stmt-items>
                 _ => parse_stmt_items(tokens),
@@ -273,6 +300,33 @@ fn parse_block_statements<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Vec<Stmt<'a>>
     panic!("Missing EOF")
 }
 
+// <try-statements>
+fn parse_try_statements<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Vec<Stmt<'a>>> {
+    let mut statements = vec![];
+    let mut my_toks = tokens;
+    while my_toks.len() > 0 {
+        let tok = &my_toks[0];
+        match tok.token {
+            // "catch"
+            Token::Catch => {return Ok((statements, &my_toks[1..]))}
+            Token::End | Token::Eof => {return Err(ParserError{
+                actual: tok.clone(),
+                error_type: ParserErrorType::ExpectedTokens{
+                    expected: vec!(Token::Catch),
+                },
+                hint: Some("`try` blocks must have a `catch` before the `end`")
+            });},
+            // <stmt> <try-statements>
+            _ => {
+                let (parsed_stmt, tokens_after_stmt) = try!(parse_stmt(my_toks));
+                statements.push(parsed_stmt);
+                my_toks = tokens_after_stmt;
+            }
+        }
+    }
+    panic!("Missing EOF")
+}
+
 // <clip-statements>
 pub fn parse_clip_statements<'a>(tokens: &'a[Tok]) -> ParseResult<'a, Vec<Stmt<'a>>> {
     let mut statements = vec![];
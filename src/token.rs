@@ -1,5 +1,7 @@
 use std::fmt;
 
+use num::bigint::BigInt;
+
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
 pub enum Token<'a>{
     // Keywords
@@ -16,6 +18,10 @@ pub enum Token<'a>{
     For,
     End,
     Do,
+    Break,
+    Continue,
+    Try,
+    Catch,
 
     // Symbols
     Eof,
@@ -36,9 +42,14 @@ pub enum Token<'a>{
     // User values
     Bool(bool),
     Int(i64),
+    //Int literals too big for an i64
+    BigInt(BigInt),
     Float(f64),
     Ident(&'a str),
     String(String),
+    //Around the parts of a string with `{}`s in it
+    InterpStart,
+    InterpEnd,
 
     // Operators
     Not,
@@ -76,6 +87,10 @@ impl<'a> fmt::Display for Token<'a> {
             Token::For => "for".to_string(),
             Token::End => "end".to_string(),
             Token::Do => "do".to_string(),
+            Token::Break => "break".to_string(),
+            Token::Continue => "continue".to_string(),
+            Token::Try => "try".to_string(),
+            Token::Catch => "catch".to_string(),
 
             // Symbols
             Token::Eof => "EOF".to_string(),
@@ -96,9 +111,12 @@ impl<'a> fmt::Display for Token<'a> {
             // Token::User values
             Token::Bool(b) => b.to_string(),
             Token::Int(i) => i.to_string(),
+            Token::BigInt(ref n) => n.to_string(),
             Token::Float(f) => f.to_string(),
             Token::Ident(ref s) => s.to_string(),
             Token::String(ref s) => format!("\"{}\"", s),
+            Token::InterpStart => "start of string".to_string(),
+            Token::InterpEnd => "end of string".to_string(),
 
             // Token::Operators
             Token::Not => "!".to_string(),